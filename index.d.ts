@@ -3,7 +3,12 @@
 export declare class BehaviourApi {
   disableSignatureChecks(): void
   enableSignatureChecks(): void
+  enableAbortStackTraces(): void
+  disableAbortStackTraces(): void
   setRejectNextTransaction(reason: string): void
+  setGasMode(mode: string): void
+  getGasMode(): string
+  setCongestionControl(config?: string | undefined | null): void
   bumpCheckpoint(): void
 }
 
@@ -19,31 +24,82 @@ export declare class CoinApi {
   getCoins(address: string, structType?: string | undefined | null): string
 }
 
+export declare class CoverageApi {
+  enable(): void
+  disable(): void
+  reset(): void
+  exportMoveCoverage(): Array<number>
+  exportLcov(): string
+}
+
+export declare class GasProfilerApi {
+  enable(): void
+  disable(): void
+  getProfile(digest: string): string
+}
+
 export declare class ObjectApi {
   create(object: string): void
-  get(objectId: string): string
+  createObject(input: string): string
+  patchFields(objectId: string, fields: string): number
+  transferObject(objectId: string, recipient: string): number
+  shareObject(objectId: string): number
+  freezeObject(objectId: string): number
+  addDynamicObjectField(parentId: string, objectId: string): string
+  get(objectId: string, options?: string | undefined | null): string
+  getDecoded(objectId: string, depth?: number | undefined | null): string
   getPast(input: string): string
   getDynamicFields(input: string): string
   getDynamicFieldObject(input: string): string
 }
 
 export declare class PackageApi {
-  publish(modules: Array<Array<number>>, dependencyIds: Array<string>, sender: string): string
+  publish(
+    modules: Array<Array<number>>,
+    dependencyIds: Array<string>,
+    sender: string,
+    options?: string | undefined | null,
+  ): string
+  verify(modules: Array<Array<number>>, dependencyIds: Array<string>): string
+  buildPublishTransaction(
+    modules: Array<Array<number>>,
+    dependencyIds: Array<string>,
+    sender: string,
+    options?: string | undefined | null,
+  ): string
+  publishFromPath(path: string, sender: string): string
+  upgrade(
+    packageId: string,
+    upgradeCapId: string,
+    modules: Array<Array<number>>,
+    dependencyIds: Array<string>,
+    policy: string,
+    options?: string | undefined | null,
+  ): string
+  getVersions(packageId: string): string
+  register(name: string, packageId: string): void
+  resolve(name: string): string
+  expandType(typeStr: string): string
   getNormalizedMoveFunction(packageId: string, module: string, fun: string): string
+  getNormalizedMoveModulesByPackage(packageId: string): string
+  getNormalizedMoveModule(packageId: string, module: string): string
+  getNormalizedMoveStruct(packageId: string, module: string, structure: string): string
+  getMoveFunctionArgTypes(packageId: string, module: string, fun: string): string
 }
 
 export declare class StateApi {
   getLatestCheckpoint(): number
   getReferenceGasPrice(): number
+  getProtocolConfig(): string
 }
 
 export declare class StorageApi {
-  takeSnapshot(): Array<number>
+  takeSnapshot(includeDebugInfo?: boolean | undefined | null): Array<number>
   restoreFromSnapshot(snapshot: Array<number>): void
 }
 
 export declare class SuiSandbox {
-  constructor()
+  constructor(options?: string | undefined | null)
   clockApi(): ClockApi
   objectApi(): ObjectApi
   transactionApi(): TransactionApi
@@ -52,11 +108,18 @@ export declare class SuiSandbox {
   behaviourApi(): BehaviourApi
   stateApi(): StateApi
   storageApi(): StorageApi
+  coverageApi(): CoverageApi
+  gasProfilerApi(): GasProfilerApi
 }
 
 export declare class TransactionApi {
   dryRun(transactionData: string): string
   execute(transactionData: string, signatures: Array<string>): string
+  lock(transactionData: string, signatures: Array<string>): string
+  submit(transactionData: string, signatures: Array<string>): string
+  pending(): Array<string>
+  sealBatch(strategy?: string | undefined | null): string
+  executeWithTrace(transactionData: string, signatures: Array<string>): string
   getResponse(digest: string): string
   queryBlocks(params: string): string
 }
@@ -0,0 +1,7 @@
+module dependent::dependent;
+
+use shared::shared::{Self, Test};
+
+public fun double(test: &mut Test, value: u8) {
+    shared::set_value(test, value * 2);
+}
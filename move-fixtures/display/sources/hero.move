@@ -0,0 +1,38 @@
+module display::hero;
+
+use std::string::{String, utf8};
+use sui::display;
+use sui::package;
+
+public struct HERO has drop {}
+
+public struct Hero has key, store {
+    id: UID,
+    name: String,
+    level: u64,
+}
+
+fun init(otw: HERO, ctx: &mut TxContext) {
+    let publisher = package::claim(otw, ctx);
+
+    let mut hero_display = display::new_with_fields<Hero>(
+        &publisher,
+        vector[utf8(b"name"), utf8(b"description")],
+        vector[utf8(b"{name}"), utf8(b"Level {level} hero")],
+        ctx,
+    );
+    hero_display.update_version();
+
+    transfer::public_transfer(publisher, ctx.sender());
+    transfer::public_transfer(hero_display, ctx.sender());
+}
+
+public fun mint(name: String, ctx: &mut TxContext) {
+    let hero = Hero {
+        id: object::new(ctx),
+        name,
+        level: 1,
+    };
+
+    transfer::public_transfer(hero, ctx.sender());
+}
@@ -0,0 +1,49 @@
+module pool::pool;
+
+const ENotEnoughLiquidity: u64 = 3;
+
+#[error]
+const EPoolEmpty: vector<u8> = b"Pool has no liquidity left";
+
+public struct Pool has key {
+    id: UID,
+    liquidity: u64,
+}
+
+public fun new(ctx: &mut TxContext) {
+    let pool = Pool {
+        id: object::new(ctx),
+        liquidity: 0,
+    };
+
+    transfer::share_object(pool);
+}
+
+public fun withdraw(pool: &mut Pool, amount: u64) {
+    assert!(pool.liquidity > 0, EPoolEmpty);
+    assert!(pool.liquidity >= amount, ENotEnoughLiquidity);
+
+    pool.liquidity = pool.liquidity - amount;
+}
+
+public fun withdraw_unchecked(pool: &mut Pool, amount: u64) {
+    assert!(pool.liquidity >= amount, ENotEnoughLiquidity);
+
+    pool.liquidity = pool.liquidity - amount;
+}
+
+public fun withdraw_nested(pool: &mut Pool, amount: u64) {
+    check_liquidity(pool, amount);
+
+    pool.liquidity = pool.liquidity - amount;
+}
+
+fun check_liquidity(pool: &Pool, amount: u64) {
+    assert!(pool.liquidity >= amount, ENotEnoughLiquidity);
+}
+
+public fun countdown(mut steps: u64) {
+    while (steps > 0) {
+        steps = steps - 1;
+    }
+}
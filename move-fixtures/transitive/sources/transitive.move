@@ -0,0 +1,8 @@
+module transitive::transitive;
+
+use dependent::dependent;
+use shared::shared::Test;
+
+public fun quadruple(test: &mut Test, value: u8) {
+    dependent::double(test, value * 2);
+}
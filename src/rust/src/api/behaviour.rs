@@ -1,6 +1,13 @@
+use std::str::FromStr;
+
+use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
-use crate::SharedState;
+use crate::{
+    sandbox::{CongestionConfig, GasMode},
+    utils::deserialize_json,
+    SharedState,
+};
 
 #[napi]
 pub struct BehaviourApi {
@@ -23,11 +30,48 @@ impl BehaviourApi {
         self.sandbox.borrow_mut().enable_signature_checks();
     }
 
+    #[napi]
+    pub fn enable_abort_stack_traces(&self) {
+        self.sandbox.borrow_mut().enable_abort_stack_traces();
+    }
+
+    #[napi]
+    pub fn disable_abort_stack_traces(&self) {
+        self.sandbox.borrow_mut().disable_abort_stack_traces();
+    }
+
     #[napi]
     pub fn set_reject_next_transaction(&self, reason: String) {
         self.sandbox.borrow_mut().reject_next_tx(reason);
     }
 
+    #[napi]
+    pub fn set_gas_mode(&self, mode: String) -> Result<()> {
+        let mode = GasMode::from_str(&mode)
+            .map_err(|e| Error::from_reason(format!("Invalid gas mode: {}", e)))?;
+
+        self.sandbox.borrow_mut().set_gas_mode(mode);
+
+        Ok(())
+    }
+
+    #[napi]
+    pub fn get_gas_mode(&self) -> String {
+        self.sandbox.borrow().gas_mode().as_str().to_string()
+    }
+
+    // `config` is a JSON encoded `CongestionConfig`, `None` disables congestion control
+    #[napi]
+    pub fn set_congestion_control(&self, config: Option<String>) -> Result<()> {
+        let config = config
+            .map(|config| deserialize_json::<CongestionConfig>(&config))
+            .transpose()?;
+
+        self.sandbox.borrow_mut().set_congestion_control(config);
+
+        Ok(())
+    }
+
     #[napi]
     pub fn bump_checkpoint(&self) {
         self.sandbox.borrow_mut().storage_mut().bump_checkpoint();
@@ -0,0 +1,45 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::SharedState;
+
+#[napi]
+pub struct CoverageApi {
+    sandbox: SharedState,
+}
+
+#[napi]
+impl CoverageApi {
+    pub fn new(sandbox: SharedState) -> Self {
+        Self { sandbox }
+    }
+
+    #[napi]
+    pub fn enable(&self) {
+        self.sandbox.borrow_mut().coverage_mut().enable();
+    }
+
+    #[napi]
+    pub fn disable(&self) {
+        self.sandbox.borrow_mut().coverage_mut().disable();
+    }
+
+    #[napi]
+    pub fn reset(&self) {
+        self.sandbox.borrow_mut().coverage_mut().reset();
+    }
+
+    // bcs encoded `CoverageMap`, the `.coverage_map.mvcov` format
+    #[napi]
+    pub fn export_move_coverage(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(&self.sandbox.borrow().coverage().to_coverage_map())
+            .map_err(|e| Error::from_reason(format!("Error while exporting coverage: {e}.")))
+    }
+
+    #[napi]
+    pub fn export_lcov(&self) -> String {
+        let sandbox = self.sandbox.borrow();
+
+        sandbox.coverage().to_lcov(sandbox.storage())
+    }
+}
@@ -0,0 +1,38 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::{to_json, utils::parse_digest, SharedState};
+
+#[napi]
+pub struct GasProfilerApi {
+    sandbox: SharedState,
+}
+
+#[napi]
+impl GasProfilerApi {
+    pub fn new(sandbox: SharedState) -> Self {
+        Self { sandbox }
+    }
+
+    #[napi]
+    pub fn enable(&self) {
+        self.sandbox.borrow_mut().gas_profiler_mut().enable();
+    }
+
+    #[napi]
+    pub fn disable(&self) {
+        self.sandbox.borrow_mut().gas_profiler_mut().disable();
+    }
+
+    // only transactions executed while the profiler was enabled have a profile
+    #[napi]
+    pub fn get_profile(&self, digest: String) -> Result<String> {
+        let transaction_digest = parse_digest(&digest)?;
+
+        to_json!(self
+            .sandbox
+            .borrow()
+            .gas_profiler()
+            .profile(&transaction_digest))
+    }
+}
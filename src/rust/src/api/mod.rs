@@ -1,6 +1,8 @@
 pub mod behaviour;
 pub mod clock;
 pub mod coin;
+pub mod coverage;
+pub mod gas_profiler;
 pub mod object;
 pub mod package;
 pub mod state;
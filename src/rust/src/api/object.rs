@@ -7,10 +7,14 @@ use sui_types::{
 };
 
 use crate::{
-    dynamic_utils::dynamic_field_info,
+    dynamic_utils::{decoded_object, dynamic_field_info},
+    sandbox::DisplayExtension,
     to_json,
-    types::{GetDynamicFieldObjectParams, GetDynamicFieldsParams, TryGetPastObjectParams},
-    utils::{deserialize_json, parse_object_id},
+    types::{
+        CreateObjectParams, GetDynamicFieldObjectParams, GetDynamicFieldsParams,
+        TryGetPastObjectParams,
+    },
+    utils::{deserialize_json, parse_address, parse_object_id, parse_struct_tag},
     SharedState,
 };
 
@@ -35,19 +39,127 @@ impl ObjectApi {
     }
 
     #[napi]
-    pub fn get(&self, object_id: String) -> Result<String> {
-        let object = self
+    pub fn create_object(&self, input: String) -> Result<String> {
+        let input: CreateObjectParams = deserialize_json(&input)?;
+
+        let id = self
             .sandbox
-            .borrow()
-            .get_object(parse_object_id(&object_id)?);
-        let response = SuiObjectResponse::try_from((object, SuiObjectDataOptions::full_content()))
+            .borrow_mut()
+            .object_mut()
+            .create_typed_object(parse_struct_tag(&input.type_)?, input.fields, input.owner)
+            .map_err(|e| Error::from_reason(format!("Failed to create object: {}", e)))?;
+
+        Ok(id.to_hex_literal())
+    }
+
+    #[napi]
+    pub fn patch_fields(&self, object_id: String, fields: String) -> Result<i64> {
+        let version = self
+            .sandbox
+            .borrow_mut()
+            .object_mut()
+            .patch_fields(parse_object_id(&object_id)?, deserialize_json(&fields)?)
+            .map_err(|e| Error::from_reason(format!("Failed to patch object: {}", e)))?;
+
+        Ok(version.value() as i64)
+    }
+
+    #[napi]
+    pub fn transfer_object(&self, object_id: String, recipient: String) -> Result<i64> {
+        let version = self
+            .sandbox
+            .borrow_mut()
+            .object_mut()
+            .transfer_object(parse_object_id(&object_id)?, parse_address(&recipient)?)
+            .map_err(|e| Error::from_reason(format!("Failed to transfer object: {}", e)))?;
+
+        Ok(version.value() as i64)
+    }
+
+    #[napi]
+    pub fn share_object(&self, object_id: String) -> Result<i64> {
+        let version = self
+            .sandbox
+            .borrow_mut()
+            .object_mut()
+            .share_object(parse_object_id(&object_id)?)
+            .map_err(|e| Error::from_reason(format!("Failed to share object: {}", e)))?;
+
+        Ok(version.value() as i64)
+    }
+
+    #[napi]
+    pub fn freeze_object(&self, object_id: String) -> Result<i64> {
+        let version = self
+            .sandbox
+            .borrow_mut()
+            .object_mut()
+            .freeze_object(parse_object_id(&object_id)?)
+            .map_err(|e| Error::from_reason(format!("Failed to freeze object: {}", e)))?;
+
+        Ok(version.value() as i64)
+    }
+
+    #[napi]
+    pub fn add_dynamic_object_field(&self, parent_id: String, object_id: String) -> Result<String> {
+        let field_id = self
+            .sandbox
+            .borrow_mut()
+            .object_mut()
+            .add_dynamic_object_field(parse_object_id(&parent_id)?, parse_object_id(&object_id)?)
             .map_err(|e| {
-                Error::from_reason(format!("Failed to construct object response: {}", e))
+                Error::from_reason(format!("Failed to add dynamic object field: {}", e))
             })?;
 
+        Ok(field_id.to_hex_literal())
+    }
+
+    #[napi]
+    pub fn get(&self, object_id: String, options: Option<String>) -> Result<String> {
+        let object_id = parse_object_id(&object_id)?;
+        let requested: SuiObjectDataOptions = options
+            .map(|options| deserialize_json(&options))
+            .transpose()?
+            .unwrap_or_default();
+
+        // content is always returned, options only opt into the extra display rendering
+        let options = SuiObjectDataOptions {
+            show_display: requested.show_display,
+            ..SuiObjectDataOptions::full_content()
+        };
+
+        let sandbox = self.sandbox.borrow();
+        let object = sandbox.get_object(object_id);
+        let mut response = SuiObjectResponse::try_from((object, options.clone())).map_err(|e| {
+            Error::from_reason(format!("Failed to construct object response: {}", e))
+        })?;
+
+        if let (true, Some(data), Some(object)) = (
+            options.show_display,
+            response.data.as_mut(),
+            sandbox.storage().get_object(&object_id),
+        ) {
+            data.display = Some(sandbox.storage().render_display(object));
+        }
+
         to_json!(response)
     }
 
+    #[napi]
+    pub fn get_decoded(&self, object_id: String, depth: Option<u32>) -> Result<String> {
+        let object_id = parse_object_id(&object_id)?;
+        let sandbox = self.sandbox.borrow();
+        let object = sandbox
+            .storage()
+            .get_object(&object_id)
+            .ok_or_else(|| Error::from_reason(format!("No object: {}", object_id)))?;
+
+        let decoded = decoded_object(object, sandbox.storage(), depth.unwrap_or(1))
+            .map_err(|e| Error::from_reason(format!("Failed to decode object: {}", e)))?;
+
+        to_json!(decoded)
+    }
+
     #[napi]
     pub fn get_past(&self, input: String) -> Result<String> {
         let input: TryGetPastObjectParams = serde_json::from_str(&input)
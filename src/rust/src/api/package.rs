@@ -1,11 +1,21 @@
+use std::path::Path;
+
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use sui_types::{base_types::ObjectID, move_package::UpgradePolicy};
 
 use crate::{
+    sandbox::PublishVerificationError,
     to_json,
-    utils::{parse_address, parse_object_id},
+    types::PublishOptions,
+    utils::{deserialize_json, encode_base64, parse_address, parse_object_id, serialize_bcs},
     SharedState,
 };
+
+// prefix of the error message carrying verification diagnostics as JSON, the TS client turns
+// it into a `PublishError`
+const PUBLISH_DIAGNOSTICS_PREFIX: &str = "PublishVerificationError:";
+
 #[napi]
 pub struct PackageApi {
     sandbox: SharedState,
@@ -23,23 +33,143 @@ impl PackageApi {
         modules: Vec<Vec<u8>>,
         dependency_ids: Vec<String>,
         sender: String,
+        options: Option<String>,
+    ) -> Result<String> {
+        let sender_address = parse_address(&sender)?;
+        let dep_object_ids = parse_object_ids(&dependency_ids)?;
+        let options = parse_publish_options(options)?;
+
+        let res = self
+            .sandbox
+            .borrow_mut()
+            .package_mut()
+            .publish_package_with_options(sender_address, modules, dep_object_ids, &options)
+            .map_err(|e| publish_error("Publishing package failed", e))?;
+
+        to_json!(res)
+    }
+
+    #[napi]
+    pub fn verify(&self, modules: Vec<Vec<u8>>, dependency_ids: Vec<String>) -> Result<String> {
+        let dep_object_ids = parse_object_ids(&dependency_ids)?;
+
+        let diagnostics = self
+            .sandbox
+            .borrow()
+            .package()
+            .verify_package(&modules, &dep_object_ids)
+            .map_err(|e| Error::from_reason(format!("Verifying package failed: {}", e)))?;
+
+        to_json!(diagnostics)
+    }
+
+    #[napi]
+    pub fn build_publish_transaction(
+        &self,
+        modules: Vec<Vec<u8>>,
+        dependency_ids: Vec<String>,
+        sender: String,
+        options: Option<String>,
     ) -> Result<String> {
         let sender_address = parse_address(&sender)?;
-        let dep_object_ids = dependency_ids
-            .iter()
-            .map(|id| parse_object_id(id))
-            .collect::<Result<Vec<_>, _>>()?;
+        let dep_object_ids = parse_object_ids(&dependency_ids)?;
+        let options = parse_publish_options(options)?;
+
+        let tx = self
+            .sandbox
+            .borrow_mut()
+            .package_mut()
+            .build_publish_transaction(sender_address, modules, dep_object_ids, &options)
+            .map_err(|e| publish_error("Building publish transaction failed", e))?;
+
+        Ok(encode_base64(&serialize_bcs(&tx)?))
+    }
+
+    #[napi]
+    pub fn publish_from_path(&self, path: String, sender: String) -> Result<String> {
+        let sender_address = parse_address(&sender)?;
+
+        let res = self
+            .sandbox
+            .borrow_mut()
+            .package_mut()
+            .publish_from_path(sender_address, Path::new(&path))
+            .map_err(|e| publish_error("Publishing package failed", e))?;
+
+        to_json!(res)
+    }
+
+    #[napi]
+    pub fn upgrade(
+        &self,
+        package_id: String,
+        upgrade_cap_id: String,
+        modules: Vec<Vec<u8>>,
+        dependency_ids: Vec<String>,
+        policy: String,
+        options: Option<String>,
+    ) -> Result<String> {
+        let dep_object_ids = parse_object_ids(&dependency_ids)?;
+        let options = parse_publish_options(options)?;
 
         let res = self
             .sandbox
             .borrow_mut()
             .package_mut()
-            .publish_package(sender_address, modules, dep_object_ids)
-            .map_err(|e| Error::from_reason(format!("Publishing package failed: {}", e)))?;
+            .upgrade_package(
+                parse_object_id(&package_id)?,
+                parse_object_id(&upgrade_cap_id)?,
+                modules,
+                dep_object_ids,
+                parse_upgrade_policy(&policy)?,
+                &options,
+            )
+            .map_err(|e| publish_error("Upgrading package failed", e))?;
 
         to_json!(res)
     }
 
+    #[napi]
+    pub fn get_versions(&self, package_id: String) -> Result<String> {
+        let versions = self
+            .sandbox
+            .borrow()
+            .storage()
+            .package_versions(&parse_object_id(&package_id)?);
+
+        to_json!(versions)
+    }
+
+    #[napi]
+    pub fn register(&self, name: String, package_id: String) -> Result<()> {
+        self.sandbox
+            .borrow_mut()
+            .package_mut()
+            .register_package(name, parse_object_id(&package_id)?)
+            .map_err(|e| Error::from_reason(format!("Registering package failed: {}", e)))
+    }
+
+    #[napi]
+    pub fn resolve(&self, name: String) -> Result<String> {
+        let resolved = self
+            .sandbox
+            .borrow()
+            .package()
+            .resolve_package(&name)
+            .map_err(|e| Error::from_reason(format!("Failed to resolve package: {}", e)))?;
+
+        to_json!(resolved)
+    }
+
+    #[napi]
+    pub fn expand_type(&self, type_str: String) -> Result<String> {
+        self.sandbox
+            .borrow()
+            .package()
+            .expand_type(&type_str)
+            .map_err(|e| Error::from_reason(format!("Failed to expand type: {}", e)))
+    }
+
     #[napi]
     pub fn get_normalized_move_function(
         &self,
@@ -56,4 +186,98 @@ impl PackageApi {
 
         to_json!(normalized)
     }
+
+    #[napi]
+    pub fn get_normalized_move_modules_by_package(&self, package_id: String) -> Result<String> {
+        let normalized = self
+            .sandbox
+            .borrow()
+            .package()
+            .get_normalized_move_modules_by_package(package_id)
+            .map_err(|e| Error::from_reason(format!("Failed to get normalized modules: {}", e)))?;
+
+        to_json!(normalized)
+    }
+
+    #[napi]
+    pub fn get_normalized_move_module(&self, package_id: String, module: String) -> Result<String> {
+        let normalized = self
+            .sandbox
+            .borrow()
+            .package()
+            .get_normalized_move_module(package_id, module)
+            .map_err(|e| Error::from_reason(format!("Failed to get normalized module: {}", e)))?;
+
+        to_json!(normalized)
+    }
+
+    #[napi]
+    pub fn get_normalized_move_struct(
+        &self,
+        package_id: String,
+        module: String,
+        structure: String,
+    ) -> Result<String> {
+        let normalized = self
+            .sandbox
+            .borrow()
+            .package()
+            .get_normalized_move_struct(package_id, module, structure)
+            .map_err(|e| Error::from_reason(format!("Failed to get normalized struct: {}", e)))?;
+
+        to_json!(normalized)
+    }
+
+    #[napi]
+    pub fn get_move_function_arg_types(
+        &self,
+        package_id: String,
+        module: String,
+        fun: String,
+    ) -> Result<String> {
+        let arg_types = self
+            .sandbox
+            .borrow()
+            .package()
+            .get_move_function_arg_types(package_id, module, fun)
+            .map_err(|e| Error::from_reason(format!("Failed to get function arg types: {}", e)))?;
+
+        to_json!(arg_types)
+    }
+}
+
+fn parse_upgrade_policy(policy: &str) -> Result<UpgradePolicy> {
+    match policy {
+        "compatible" => Ok(UpgradePolicy::Compatible),
+        "additive" => Ok(UpgradePolicy::Additive),
+        "dep_only" => Ok(UpgradePolicy::DepOnly),
+        _ => Err(Error::from_reason(format!(
+            "Invalid upgrade policy: {} - expected compatible, additive or dep_only",
+            policy
+        ))),
+    }
+}
+
+fn parse_object_ids(ids: &[String]) -> Result<Vec<ObjectID>> {
+    ids.iter().map(|id| parse_object_id(id)).collect()
+}
+
+fn parse_publish_options(options: Option<String>) -> Result<PublishOptions> {
+    Ok(options
+        .map(|options| deserialize_json(&options))
+        .transpose()?
+        .unwrap_or_default())
+}
+
+fn publish_error(context: &str, error: anyhow::Error) -> Error {
+    let diagnostics = error
+        .downcast_ref::<PublishVerificationError>()
+        .and_then(|verification| serde_json::to_string(&verification.diagnostics).ok());
+
+    match diagnostics {
+        Some(diagnostics) => {
+            Error::from_reason(format!("{PUBLISH_DIAGNOSTICS_PREFIX}{diagnostics}"))
+        }
+        None => Error::from_reason(format!("{context}: {error}")),
+    }
 }
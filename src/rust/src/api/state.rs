@@ -1,6 +1,8 @@
+use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use sui_json_rpc_types::ProtocolConfigResponse;
 
-use crate::SharedState;
+use crate::{to_json, SharedState};
 
 #[napi]
 pub struct StateApi {
@@ -22,4 +24,11 @@ impl StateApi {
     pub fn get_reference_gas_price(&self) -> i64 {
         self.sandbox.borrow().gas_price() as i64
     }
+
+    #[napi]
+    pub fn get_protocol_config(&self) -> Result<String> {
+        let config = self.sandbox.borrow().protocol_config().clone();
+
+        to_json!(ProtocolConfigResponse::from(config))
+    }
 }
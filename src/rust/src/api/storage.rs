@@ -15,11 +15,11 @@ impl StorageApi {
     }
 
     #[napi]
-    pub fn take_snapshot(&self) -> Result<Vec<u8>> {
+    pub fn take_snapshot(&self, include_debug_info: Option<bool>) -> Result<Vec<u8>> {
         self.sandbox
             .borrow()
             .storage()
-            .to_bytes_compressed()
+            .to_bytes_compressed(include_debug_info.unwrap_or(false))
             .map_err(|e| Error::from_reason(format!("Error while creating snapshot: {e}.")))
     }
 
@@ -27,8 +27,7 @@ impl StorageApi {
     pub fn restore_from_snapshot(&self, snapshot: Vec<u8>) -> Result<()> {
         self.sandbox
             .borrow_mut()
-            .storage_mut()
-            .restone_from_bytes(&snapshot)
+            .restore_from_snapshot(&snapshot)
             .map_err(|e| Error::from_reason(format!("Error while restoring snapshot: {e}.")))
     }
 }
@@ -1,11 +1,19 @@
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
-use sui_json_rpc_types::{Page, SuiTransactionBlockResponseQuery};
-use sui_types::base_types::ObjectID;
+use sui_json_rpc_types::{Page, SuiTransactionBlockResponse, SuiTransactionBlockResponseQuery};
+use sui_types::{
+    base_types::ObjectID, crypto::Signature, effects::TransactionEffects,
+    transaction::TransactionData,
+};
 
 use crate::{
+    sandbox::{AbortExtension, MoveVMSandbox, OrderingStrategy},
     to_json,
-    utils::{decode_base64, deserialize_bcs, parse_digest, parse_signature},
+    types::{SealedTransaction, TracedExecution, WithAbort},
+    utils::{
+        decode_base64, deserialize_bcs, deserialize_json, encode_base64, parse_digest,
+        parse_signature,
+    },
     SharedState,
 };
 
@@ -36,12 +44,7 @@ impl TransactionApi {
 
     #[napi]
     pub fn execute(&self, transaction_data: String, signatures: Vec<String>) -> Result<String> {
-        let tx_bytes = decode_base64(&transaction_data)?;
-        let tx_data = deserialize_bcs(&tx_bytes)?;
-        let parsed_signatures = signatures
-            .iter()
-            .map(|s| parse_signature(s))
-            .collect::<Result<Vec<_>, _>>()?;
+        let (tx_data, parsed_signatures) = parse_transaction(&transaction_data, &signatures)?;
 
         let result = self
             .sandbox
@@ -50,18 +53,113 @@ impl TransactionApi {
             .execute_function(tx_data, parsed_signatures)
             .map_err(|e| Error::from_reason(format!("Transaction execution failed: {}", e)))?;
 
-        to_json!(result)
+        to_json!(with_abort(&self.sandbox.borrow(), result))
     }
 
+    // returns the digest of the transaction the owned inputs are now locked for
     #[napi]
-    pub fn get_response(&self, digest: String) -> Result<String> {
-        let transaction_digest = parse_digest(&digest)?;
-        let response = self
+    pub fn lock(&self, transaction_data: String, signatures: Vec<String>) -> Result<String> {
+        let (tx_data, parsed_signatures) = parse_transaction(&transaction_data, &signatures)?;
+
+        let digest = self
             .sandbox
+            .borrow_mut()
+            .transaction_mut()
+            .lock_transaction(tx_data, parsed_signatures)
+            .map_err(|e| Error::from_reason(format!("Transaction rejected: {}", e)))?;
+
+        Ok(digest.to_string())
+    }
+
+    // queues the transaction until the next `seal_batch`, returns its digest
+    #[napi]
+    pub fn submit(&self, transaction_data: String, signatures: Vec<String>) -> Result<String> {
+        let (tx_data, parsed_signatures) = parse_transaction(&transaction_data, &signatures)?;
+
+        let digest = self
+            .sandbox
+            .borrow_mut()
+            .transaction_mut()
+            .submit_transaction(tx_data, parsed_signatures)
+            .map_err(|e| Error::from_reason(format!("Transaction rejected: {}", e)))?;
+
+        Ok(digest.to_string())
+    }
+
+    #[napi]
+    pub fn pending(&self) -> Vec<String> {
+        self.sandbox
             .borrow()
+            .mempool()
+            .pending()
+            .iter()
+            .map(|digest| digest.to_string())
+            .collect()
+    }
+
+    // `strategy` is a JSON encoded `OrderingStrategy`, FIFO when omitted
+    #[napi]
+    pub fn seal_batch(&self, strategy: Option<String>) -> Result<String> {
+        let strategy: OrderingStrategy = strategy
+            .map(|strategy| deserialize_json(&strategy))
+            .transpose()?
+            .unwrap_or_default();
+
+        let results = self
+            .sandbox
+            .borrow_mut()
+            .transaction_mut()
+            .seal_batch(strategy);
+
+        let sandbox = self.sandbox.borrow();
+
+        to_json!(results
+            .into_iter()
+            .map(|(digest, result)| match result {
+                Ok(response) => SealedTransaction {
+                    digest,
+                    response: Some(with_abort(&sandbox, response)),
+                    error: None,
+                },
+                Err(e) => SealedTransaction {
+                    digest,
+                    response: None,
+                    error: Some(format!("Transaction execution failed: {}", e)),
+                },
+            })
+            .collect::<Vec<_>>())
+    }
+
+    #[napi]
+    pub fn execute_with_trace(
+        &self,
+        transaction_data: String,
+        signatures: Vec<String>,
+    ) -> Result<String> {
+        let (tx_data, parsed_signatures) = parse_transaction(&transaction_data, &signatures)?;
+
+        let (response, trace) = self
+            .sandbox
+            .borrow_mut()
+            .transaction_mut()
+            .execute_function_traced(tx_data, parsed_signatures)
+            .map_err(|e| Error::from_reason(format!("Transaction execution failed: {}", e)))?;
+
+        to_json!(TracedExecution {
+            response: with_abort(&self.sandbox.borrow(), response),
+            trace: trace.map(|trace| encode_base64(&trace.into_compressed_json_bytes())),
+        })
+    }
+
+    #[napi]
+    pub fn get_response(&self, digest: String) -> Result<String> {
+        let transaction_digest = parse_digest(&digest)?;
+        let sandbox = self.sandbox.borrow();
+        let response = sandbox
             .storage()
             .get_transaction(&transaction_digest)
-            .cloned();
+            .cloned()
+            .map(|response| with_abort(&sandbox, response));
 
         to_json!(response)
     }
@@ -80,3 +178,31 @@ impl TransactionApi {
         })
     }
 }
+
+fn parse_transaction(
+    transaction_data: &str,
+    signatures: &[String],
+) -> Result<(TransactionData, Vec<Signature>)> {
+    let tx_bytes = decode_base64(transaction_data)?;
+    let tx_data = deserialize_bcs(&tx_bytes)?;
+    let parsed_signatures = signatures
+        .iter()
+        .map(|s| parse_signature(s))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((tx_data, parsed_signatures))
+}
+
+fn with_abort(
+    sandbox: &MoveVMSandbox,
+    response: SuiTransactionBlockResponse,
+) -> WithAbort<SuiTransactionBlockResponse> {
+    let abort = bcs::from_bytes::<TransactionEffects>(&response.raw_effects)
+        .ok()
+        .and_then(|effects| sandbox.storage().decode_move_abort(&effects));
+
+    WithAbort {
+        inner: response,
+        abort,
+    }
+}
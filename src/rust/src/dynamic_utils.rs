@@ -1,10 +1,18 @@
 use anyhow::anyhow;
-use move_core_types::annotated_value::MoveTypeLayout;
+use move_core_types::{
+    account_address::AccountAddress,
+    annotated_value::{MoveStruct, MoveTypeLayout, MoveValue},
+    identifier::Identifier,
+    language_storage::StructTag,
+};
+use serde_json::{json, Map, Value};
 use sui_json_rpc_types::{BcsName, DynamicFieldInfo as DynamicFieldInfoResponse, SuiMoveValue};
 use sui_types::{
+    base_types::ObjectID,
     dynamic_field::{visitor as DFV, DynamicFieldName},
-    object::{bounded_visitor::BoundedVisitor, Object},
-    TypeTag,
+    id::{ID, UID},
+    object::{bounded_visitor::BoundedVisitor, Object, Owner},
+    TypeTag, MOVE_STDLIB_ADDRESS,
 };
 
 use crate::sandbox::StorageExtension;
@@ -69,3 +77,189 @@ pub fn dynamic_field_info(
         }
     })
 }
+
+// Decodes an object into a plain JSON tree. Every struct keeps its full type next to its
+// fields, and dynamic fields attached to any UID inside it (the object itself, a Table,
+// a Bag, ...) are inlined as `dynamicFields`, `depth` levels deep.
+pub fn decoded_object(
+    object: &Object,
+    storage: &StorageExtension,
+    depth: u32,
+) -> anyhow::Result<Value> {
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or(anyhow!("Object {} is not a move-object", object.id()))?;
+
+    let layout = move_object.get_layout(storage.as_inner())?;
+    let decoded = BoundedVisitor::deserialize_struct(move_object.contents(), &layout)?;
+
+    Ok(json!({
+        "objectId": object.id(),
+        "version": object.version().value().to_string(),
+        "owner": object.owner,
+        "content": decoded_value(MoveValue::Struct(decoded), storage, depth)?,
+    }))
+}
+
+fn decoded_value(
+    value: MoveValue,
+    storage: &StorageExtension,
+    depth: u32,
+) -> anyhow::Result<Value> {
+    Ok(match value {
+        MoveValue::Bool(b) => Value::Bool(b),
+        MoveValue::U8(n) => json!(n),
+        MoveValue::U16(n) => json!(n),
+        MoveValue::U32(n) => json!(n),
+        MoveValue::U64(n) => Value::String(n.to_string()),
+        MoveValue::U128(n) => Value::String(n.to_string()),
+        MoveValue::U256(n) => Value::String(n.to_string()),
+        MoveValue::Address(address) | MoveValue::Signer(address) => {
+            Value::String(address.to_canonical_string(true))
+        }
+        MoveValue::Vector(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| decoded_value(value, storage, depth))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        MoveValue::Variant(variant) => json!({
+            "type": variant.type_.to_canonical_string(true),
+            "variant": variant.variant_name.to_string(),
+            "fields": decoded_fields(variant.fields, storage, depth)?,
+        }),
+        MoveValue::Struct(move_struct) => decoded_struct(move_struct, storage, depth)?,
+    })
+}
+
+fn decoded_struct(
+    move_struct: MoveStruct,
+    storage: &StorageExtension,
+    depth: u32,
+) -> anyhow::Result<Value> {
+    let MoveStruct { type_, fields } = move_struct;
+
+    if type_ == UID::type_() || type_ == ID::type_() {
+        return Ok(first_address(&fields)
+            .map(|address| Value::String(address.to_canonical_string(true)))
+            .unwrap_or(Value::Null));
+    }
+
+    if is_std_type(&type_, "string", "String") || is_std_type(&type_, "ascii", "String") {
+        let bytes = match fields.into_iter().next() {
+            Some((_, MoveValue::Vector(bytes))) => bytes
+                .into_iter()
+                .filter_map(|byte| match byte {
+                    MoveValue::U8(byte) => Some(byte),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        return Ok(Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+    }
+
+    if is_std_type(&type_, "option", "Option") {
+        return match fields.into_iter().next() {
+            Some((_, MoveValue::Vector(values))) => match values.into_iter().next() {
+                Some(value) => decoded_value(value, storage, depth),
+                None => Ok(Value::Null),
+            },
+            _ => Ok(Value::Null),
+        };
+    }
+
+    let uid = fields.iter().find_map(|(_, value)| match value {
+        MoveValue::Struct(inner) if inner.type_ == UID::type_() => first_address(&inner.fields),
+        _ => None,
+    });
+
+    let mut decoded = Map::new();
+    decoded.insert(
+        "type".into(),
+        Value::String(type_.to_canonical_string(true)),
+    );
+    decoded.insert("fields".into(), decoded_fields(fields, storage, depth)?);
+
+    if let (Some(uid), true) = (uid, depth > 0) {
+        decoded.insert(
+            "dynamicFields".into(),
+            decoded_dynamic_fields(ObjectID::from(uid), storage, depth - 1)?,
+        );
+    }
+
+    Ok(Value::Object(decoded))
+}
+
+fn decoded_fields(
+    fields: Vec<(Identifier, MoveValue)>,
+    storage: &StorageExtension,
+    depth: u32,
+) -> anyhow::Result<Value> {
+    Ok(Value::Object(
+        fields
+            .into_iter()
+            .map(|(name, value)| Ok((name.to_string(), decoded_value(value, storage, depth)?)))
+            .collect::<anyhow::Result<_>>()?,
+    ))
+}
+
+fn decoded_dynamic_fields(
+    parent: ObjectID,
+    storage: &StorageExtension,
+    depth: u32,
+) -> anyhow::Result<Value> {
+    let mut entries = storage
+        .objects_for(&Owner::ObjectOwner(parent.into()))
+        .map(|field_object| {
+            let move_object = field_object
+                .data
+                .try_as_move()
+                .ok_or(anyhow!("Object not a move-object"))?;
+
+            let layout =
+                MoveTypeLayout::Struct(Box::new(move_object.get_layout(storage.as_inner())?));
+            let field = DFV::FieldVisitor::deserialize(move_object.contents(), &layout)?;
+
+            let name = BoundedVisitor::deserialize_value(field.name_bytes, field.name_layout)?;
+            let value = match field.value_metadata()? {
+                DFV::ValueMetadata::DynamicField(_) => decoded_value(
+                    BoundedVisitor::deserialize_value(field.value_bytes, field.value_layout)?,
+                    storage,
+                    depth,
+                )?,
+                DFV::ValueMetadata::DynamicObjectField(object_id) => {
+                    let object = storage
+                        .get_object(&object_id)
+                        .ok_or(anyhow!("Dynamic object field {object_id} not found"))?;
+
+                    decoded_object(object, storage, depth)?
+                }
+            };
+
+            Ok(json!({
+                "name": decoded_value(name, storage, 0)?,
+                "value": value,
+            }))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // storage keeps children in a hash set, sort them so the output is stable between runs
+    entries.sort_by_key(|entry| entry["name"].to_string());
+
+    Ok(Value::Array(entries))
+}
+
+fn first_address(fields: &[(Identifier, MoveValue)]) -> Option<AccountAddress> {
+    fields.iter().find_map(|(_, value)| match value {
+        MoveValue::Address(address) => Some(*address),
+        MoveValue::Struct(inner) => first_address(&inner.fields),
+        _ => None,
+    })
+}
+
+fn is_std_type(tag: &StructTag, module: &str, name: &str) -> bool {
+    tag.address == MOVE_STDLIB_ADDRESS && tag.module.as_str() == module && tag.name.as_str() == name
+}
@@ -3,10 +3,13 @@ use std::rc::Rc;
 
 use crate::{
     api::{
-        behaviour::BehaviourApi, clock::ClockApi, coin::CoinApi, object::ObjectApi,
-        package::PackageApi, state::StateApi, storage::StorageApi, transaction::TransactionApi,
+        behaviour::BehaviourApi, clock::ClockApi, coin::CoinApi, coverage::CoverageApi,
+        gas_profiler::GasProfilerApi, object::ObjectApi, package::PackageApi, state::StateApi,
+        storage::StorageApi, transaction::TransactionApi,
     },
     sandbox::{MoveVMSandbox, SandboxBuilder},
+    types::SandboxOptions,
+    utils::{decode_base64, deserialize_json},
 };
 
 use napi::bindgen_prelude::*;
@@ -30,11 +33,42 @@ pub struct SuiSandbox {
 #[napi]
 impl SuiSandbox {
     #[napi(constructor)]
-    pub fn new() -> Result<Self> {
+    pub fn new(options: Option<String>) -> Result<Self> {
+        let options: SandboxOptions = options
+            .map(|options| deserialize_json(&options))
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut builder = SandboxBuilder::default();
+
+        if let Some(version) = options.protocol_version {
+            builder = builder
+                .with_protocol_version(version)
+                .map_err(|e| Error::from_reason(format!("Failed to build sandbox: {}", e)))?;
+        }
+
+        if let Some(mode) = options.gas_mode {
+            builder = builder.with_gas_mode(mode);
+        }
+
+        if let Some(config) = options.congestion {
+            builder = builder.with_congestion_control(config);
+        }
+
+        for package in options.framework_packages {
+            let modules = package
+                .modules
+                .iter()
+                .map(|module| decode_base64(module))
+                .collect::<Result<Vec<_>>>()?;
+
+            builder = builder.with_framework_package(package.id, modules);
+        }
+
         Ok(Self {
-            sandbox: Rc::new(RefCell::new(SandboxBuilder::default().build().map_err(
-                |e| Error::from_reason(format!("Failed to build sandbox: {}", e)),
-            )?)),
+            sandbox: Rc::new(RefCell::new(builder.build().map_err(|e| {
+                Error::from_reason(format!("Failed to build sandbox: {}", e))
+            })?)),
         })
     }
 
@@ -77,4 +111,14 @@ impl SuiSandbox {
     pub fn storage_api(&self) -> StorageApi {
         StorageApi::new(self.sandbox.clone())
     }
+
+    #[napi]
+    pub fn coverage_api(&self) -> CoverageApi {
+        CoverageApi::new(self.sandbox.clone())
+    }
+
+    #[napi]
+    pub fn gas_profiler_api(&self) -> GasProfilerApi {
+        GasProfilerApi::new(self.sandbox.clone())
+    }
 }
@@ -1,11 +1,21 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use move_binary_format::CompiledModule;
 use sui_execution::executor;
 use sui_framework::BuiltInFramework;
 use sui_types::{
-    in_memory_storage::InMemoryStorage, object::Object, supported_protocol_versions::ProtocolConfig,
+    base_types::ObjectID,
+    digests::TransactionDigest,
+    in_memory_storage::InMemoryStorage,
+    object::{Object, OBJECT_START_VERSION},
+    supported_protocol_versions::{Chain, ProtocolConfig, ProtocolVersion},
 };
 
 use crate::sandbox::{
-    extensions::auth_extension::AuthExtension, storage::StorageExtension, MoveVMSandbox,
+    extensions::{auth_extension::AuthExtension, congestion::CongestionConfig, gas_mode::GasMode},
+    storage::StorageExtension,
+    MoveVMSandbox,
 };
 
 pub struct SandboxBuilder {
@@ -14,6 +24,9 @@ pub struct SandboxBuilder {
     enable_auth: bool,
     initial_time_ms: Option<u64>,
     genesis_objects: Vec<Object>,
+    framework_packages: BTreeMap<ObjectID, Vec<Vec<u8>>>,
+    gas_mode: GasMode,
+    congestion: Option<CongestionConfig>,
 }
 
 impl Default for SandboxBuilder {
@@ -24,19 +37,57 @@ impl Default for SandboxBuilder {
             enable_auth: true,
             initial_time_ms: None,
             genesis_objects: Vec::new(),
+            framework_packages: BTreeMap::new(),
+            gas_mode: GasMode::default(),
+            congestion: None,
         }
     }
 }
 
 impl SandboxBuilder {
-    pub fn build(self) -> anyhow::Result<MoveVMSandbox> {
+    pub fn with_protocol_version(mut self, version: u64) -> anyhow::Result<Self> {
+        let config = ProtocolConfig::get_for_version_if_supported(
+            ProtocolVersion::new(version),
+            Chain::Unknown,
+        )
+        .ok_or(anyhow!(
+            "Protocol version {version} is not supported, expected {} to {}",
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64()
+        ))?;
+
+        self.protocol_config = Some(config);
+
+        Ok(self)
+    }
+
+    // replaces the bundled bytes of a system package (0x1, 0x2, 0x3, ...) at genesis,
+    // e.g. with the framework currently deployed on mainnet
+    pub fn with_framework_package(mut self, package_id: ObjectID, modules: Vec<Vec<u8>>) -> Self {
+        self.framework_packages.insert(package_id, modules);
+        self
+    }
+
+    pub fn with_gas_mode(mut self, mode: GasMode) -> Self {
+        self.gas_mode = mode;
+        self
+    }
+
+    pub fn with_congestion_control(mut self, config: CongestionConfig) -> Self {
+        self.congestion = Some(config);
+        self
+    }
+
+    pub fn build(mut self) -> anyhow::Result<MoveVMSandbox> {
         let config = self
             .protocol_config
+            .take()
             .unwrap_or_else(ProtocolConfig::get_for_max_version_UNSAFE);
 
-        let all_genesis_objects = BuiltInFramework::genesis_objects().chain(self.genesis_objects);
+        let mut all_genesis_objects = self.framework_objects()?;
+        all_genesis_objects.append(&mut self.genesis_objects);
 
-        let storage = StorageExtension::new(InMemoryStorage::new(all_genesis_objects.collect()));
+        let storage = StorageExtension::new(InMemoryStorage::new(all_genesis_objects));
 
         let mut sandbox = MoveVMSandbox {
             executor: executor(&config, false, None)?,
@@ -46,8 +97,17 @@ impl SandboxBuilder {
             auth_extension: AuthExtension::new(),
             reference_price: self.gas_price,
             transaction_control: Default::default(),
+            coverage: Default::default(),
+            gas_profiler: Default::default(),
+            abort_stack_traces: false,
+            gas_mode: self.gas_mode,
+            object_locks: Default::default(),
+            congestion: Default::default(),
+            mempool: Default::default(),
         };
 
+        sandbox.set_congestion_control(self.congestion);
+
         if !self.enable_auth {
             sandbox.disable_signature_checks();
         }
@@ -60,4 +120,44 @@ impl SandboxBuilder {
 
         Ok(sandbox)
     }
+
+    fn framework_objects(&mut self) -> anyhow::Result<Vec<Object>> {
+        let objects = BuiltInFramework::iter_system_packages()
+            .map(
+                |package| match self.framework_packages.remove(&package.id) {
+                    Some(bytes) => {
+                        let modules = bytes
+                            .iter()
+                            .map(|bytes| CompiledModule::deserialize_with_defaults(bytes))
+                            .collect::<Result<Vec<_>, _>>()?;
+
+                        if let Some(module) = modules
+                            .iter()
+                            .find(|module| ObjectID::from(*module.address()) != package.id)
+                        {
+                            anyhow::bail!(
+                                "Module {} does not belong to framework package {}",
+                                module.self_id(),
+                                package.id
+                            );
+                        }
+
+                        Ok(Object::new_system_package(
+                            &modules,
+                            OBJECT_START_VERSION,
+                            package.dependencies.clone(),
+                            TransactionDigest::genesis_marker(),
+                        ))
+                    }
+                    None => Ok(package.genesis_object()),
+                },
+            )
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if let Some(package_id) = self.framework_packages.keys().next() {
+            anyhow::bail!("{package_id} is not a framework package");
+        }
+
+        Ok(objects)
+    }
 }
@@ -1,3 +1,5 @@
 pub mod clock;
+pub mod object;
 pub mod package;
 pub mod transaction;
+pub mod verification;
@@ -0,0 +1,368 @@
+use anyhow::anyhow;
+use std::ops::{Deref, DerefMut};
+
+use move_binary_format::{file_format::Ability, CompiledModule};
+use move_core_types::{
+    annotated_value::{MoveStructLayout, MoveTypeLayout, MoveValue},
+    language_storage::{ModuleId, StructTag},
+};
+use sui_json::SuiJsonValue;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    digests::TransactionDigest,
+    dynamic_field::{derive_dynamic_field_id, DynamicFieldInfo, Field},
+    id::{ID, UID},
+    object::{
+        bounded_visitor::BoundedVisitor, MoveObject, Object, ObjectInner, Owner,
+        OBJECT_START_VERSION,
+    },
+    TypeTag,
+};
+
+use crate::sandbox::MoveVMSandbox;
+
+pub struct ObjectEngine<S> {
+    sandbox: S,
+}
+
+impl<S> ObjectEngine<S> {
+    pub fn new(sandbox: S) -> Self {
+        Self { sandbox }
+    }
+}
+
+impl<S> ObjectEngine<S>
+where
+    S: Deref<Target = MoveVMSandbox>,
+{
+    fn existing_object(&self, object_id: ObjectID) -> anyhow::Result<Object> {
+        self.sandbox
+            .storage()
+            .get_object(&object_id)
+            .cloned()
+            .ok_or(anyhow!("No object: {object_id}"))
+    }
+
+    pub fn struct_layout(&self, struct_tag: &StructTag) -> anyhow::Result<MoveStructLayout> {
+        Ok(MoveObject::get_struct_layout_from_struct_tag(
+            struct_tag.clone(),
+            self.sandbox.storage().as_inner(),
+        )?)
+    }
+
+    fn has_public_transfer(&self, struct_tag: &StructTag) -> anyhow::Result<bool> {
+        let package_id = ObjectID::from(struct_tag.address);
+        let package = self
+            .sandbox
+            .storage()
+            .get_object(&package_id)
+            .and_then(|object| object.data.try_as_package())
+            .ok_or(anyhow!("Package {package_id} not found"))?;
+
+        let module = package
+            .get_module(&ModuleId::new(
+                struct_tag.address,
+                struct_tag.module.clone(),
+            ))
+            .ok_or(anyhow!("Module {} not found in package", struct_tag.module))?;
+        let module = CompiledModule::deserialize_with_defaults(module)?;
+
+        let handle = module
+            .datatype_handles()
+            .iter()
+            .find(|handle| module.identifier_at(handle.name) == struct_tag.name.as_ident_str())
+            .ok_or(anyhow!("Struct {} not found in module", struct_tag.name))?;
+
+        if !handle.abilities.has_ability(Ability::Key) {
+            anyhow::bail!("Struct {} has no `key` ability", struct_tag.name);
+        }
+
+        Ok(handle.abilities.has_ability(Ability::Store))
+    }
+}
+
+impl<S> ObjectEngine<S>
+where
+    S: DerefMut<Target = MoveVMSandbox>,
+{
+    pub fn create_typed_object(
+        &mut self,
+        struct_tag: StructTag,
+        fields: serde_json::Map<String, serde_json::Value>,
+        owner: Owner,
+    ) -> anyhow::Result<ObjectID> {
+        if let Owner::ObjectOwner(parent) = owner {
+            anyhow::bail!(
+                "Objects can't be created under {parent}, add them as a dynamic object field instead"
+            );
+        }
+
+        let layout = self.struct_layout(&struct_tag)?;
+        let has_public_transfer = self.has_public_transfer(&struct_tag)?;
+        let object_id = ObjectID::random();
+
+        if let Some(unknown) = fields.keys().find(|key| {
+            !layout
+                .fields
+                .iter()
+                .any(|f| f.name.as_str() == key.as_str())
+        }) {
+            anyhow::bail!("Struct {} has no field `{unknown}`", struct_tag.name);
+        }
+
+        let mut contents = vec![];
+
+        for field in &layout.fields {
+            let name = field.name.as_str();
+
+            if is_uid_field(&field.layout) {
+                contents.extend(bcs::to_bytes(&UID::new(object_id))?);
+                continue;
+            }
+
+            let value = fields
+                .get(name)
+                .cloned()
+                .ok_or(anyhow!("Missing value for field `{name}`"))?;
+
+            contents.extend(encode_field(name, value, &field.layout)?);
+        }
+
+        let move_object = unsafe {
+            MoveObject::new_from_execution(
+                struct_tag.into(),
+                has_public_transfer,
+                OBJECT_START_VERSION,
+                contents,
+                &self.sandbox.config,
+            )?
+        };
+
+        let owner = match owner {
+            Owner::Shared { .. } => Owner::Shared {
+                initial_shared_version: OBJECT_START_VERSION,
+            },
+            owner => owner,
+        };
+
+        let object = Object::new_move(move_object, owner, TransactionDigest::genesis_marker());
+        self.sandbox.storage_mut().insert_object(object);
+
+        Ok(object_id)
+    }
+
+    pub fn patch_fields(
+        &mut self,
+        object_id: ObjectID,
+        fields: serde_json::Map<String, serde_json::Value>,
+    ) -> anyhow::Result<SequenceNumber> {
+        let object = self.existing_object(object_id)?;
+
+        let move_object = object
+            .data
+            .try_as_move()
+            .ok_or(anyhow!("Object {object_id} is not a Move object"))?;
+        let layout = move_object.get_layout(self.sandbox.storage().as_inner())?;
+
+        let mut decoded = BoundedVisitor::deserialize_struct(move_object.contents(), &layout)?;
+
+        for (name, value) in fields {
+            let field = layout
+                .fields
+                .iter()
+                .find(|f| f.name.as_str() == name)
+                .ok_or(anyhow!(
+                    "Struct {} has no field `{name}`",
+                    layout.type_.name
+                ))?;
+
+            if is_uid_field(&field.layout) {
+                anyhow::bail!("Field `{name}` holds the object UID and can't be patched");
+            }
+
+            let bytes = encode_field(&name, value, &field.layout)?;
+            let new_value = BoundedVisitor::deserialize_value(&bytes, &field.layout)?;
+
+            if let Some((_, current)) = decoded
+                .fields
+                .iter_mut()
+                .find(|(ident, _)| ident.as_str() == name)
+            {
+                *current = new_value;
+            }
+        }
+
+        let contents = MoveValue::Struct(decoded)
+            .simple_serialize()
+            .ok_or(anyhow!("Failed to serialize patched object {object_id}"))?;
+
+        let config = self.sandbox.config.clone();
+
+        self.rewrite_object(object, |inner| {
+            inner
+                .data
+                .try_as_move_mut()
+                .expect("Checked above to be a Move object")
+                .update_contents(contents, &config)?;
+
+            Ok(())
+        })
+    }
+
+    pub fn transfer_object(
+        &mut self,
+        object_id: ObjectID,
+        recipient: SuiAddress,
+    ) -> anyhow::Result<SequenceNumber> {
+        self.set_owner(object_id, |_| Owner::AddressOwner(recipient))
+    }
+
+    pub fn share_object(&mut self, object_id: ObjectID) -> anyhow::Result<SequenceNumber> {
+        let object = self.existing_object(object_id)?;
+
+        // resharing would change the start version every existing shared reference points to
+        if object.is_shared() {
+            return Ok(object.version());
+        }
+
+        self.set_owner(object_id, |version| Owner::Shared {
+            initial_shared_version: version,
+        })
+    }
+
+    pub fn freeze_object(&mut self, object_id: ObjectID) -> anyhow::Result<SequenceNumber> {
+        self.set_owner(object_id, |_| Owner::Immutable)
+    }
+
+    // attaches the object to `parent_id` the same way `dynamic_object_field::add` does,
+    // using the child's `ID` as the field name
+    pub fn add_dynamic_object_field(
+        &mut self,
+        parent_id: ObjectID,
+        object_id: ObjectID,
+    ) -> anyhow::Result<ObjectID> {
+        self.existing_object(parent_id)?;
+        let object = self.existing_object(object_id)?;
+
+        if object.is_package() {
+            anyhow::bail!("Package {object_id} can't be placed under a parent");
+        }
+
+        // the field would stay behind under the previous parent, and shared or immutable
+        // objects can't be wrapped
+        if !object.owner.is_address_owned() {
+            anyhow::bail!(
+                "Object {object_id} is not address owned and can't be placed under a parent"
+            );
+        }
+
+        let id_type: TypeTag = ID::type_().into();
+        let name_type: TypeTag =
+            DynamicFieldInfo::dynamic_object_field_wrapper(id_type.clone()).into();
+        let name_bytes = bcs::to_bytes(&object_id)?;
+        let field_id = derive_dynamic_field_id(parent_id, &name_type, &name_bytes)?;
+
+        if self.sandbox.storage().get_object(&field_id).is_some() {
+            anyhow::bail!("Object {object_id} is already a dynamic field of {parent_id}");
+        }
+
+        let version = self.rewrite_object(object, |inner| {
+            inner.owner = Owner::ObjectOwner(field_id.into());
+
+            Ok(())
+        })?;
+
+        let field = Field {
+            id: UID::new(field_id),
+            name: object_id,
+            value: object_id,
+        };
+
+        let field_object = unsafe {
+            MoveObject::new_from_execution(
+                DynamicFieldInfo::dynamic_field_type(name_type, id_type).into(),
+                false,
+                version,
+                bcs::to_bytes(&field)?,
+                &self.sandbox.config,
+            )?
+        };
+
+        self.sandbox.storage_mut().insert_object(Object::new_move(
+            field_object,
+            Owner::ObjectOwner(parent_id.into()),
+            TransactionDigest::genesis_marker(),
+        ));
+
+        Ok(field_id)
+    }
+
+    fn set_owner<F: FnOnce(SequenceNumber) -> Owner>(
+        &mut self,
+        object_id: ObjectID,
+        owner: F,
+    ) -> anyhow::Result<SequenceNumber> {
+        let object = self.existing_object(object_id)?;
+
+        if object.is_package() {
+            anyhow::bail!("Package {object_id} has no owner to change");
+        }
+
+        if object.is_immutable() {
+            anyhow::bail!("Object {object_id} is immutable, its owner can't be changed");
+        }
+
+        // the child is reachable through the field object of its parent, moving it would leave
+        // the field dangling
+        if let Owner::ObjectOwner(parent) = object.owner {
+            anyhow::bail!("Object {object_id} is a child of {parent}, its owner can't be changed");
+        }
+
+        let version = object.version().next();
+
+        self.rewrite_object(object, |inner| {
+            inner.owner = owner(version);
+
+            Ok(())
+        })
+    }
+
+    // Every manual rewrite is stored as a new version, so it shows up in the object's timeline
+    // just like a change made by a transaction.
+    fn rewrite_object<F: FnOnce(&mut ObjectInner) -> anyhow::Result<()>>(
+        &mut self,
+        object: Object,
+        rewrite: F,
+    ) -> anyhow::Result<SequenceNumber> {
+        let version = object.version().next();
+        let mut inner = object.into_inner();
+
+        rewrite(&mut inner)?;
+
+        inner
+            .data
+            .try_as_move_mut()
+            .ok_or(anyhow!("Object {} is not a Move object", inner.id()))?
+            .increment_version_to(version);
+
+        self.sandbox
+            .storage_mut()
+            .insert_object(Object::from(inner));
+
+        Ok(version)
+    }
+}
+
+fn encode_field(
+    name: &str,
+    value: serde_json::Value,
+    layout: &MoveTypeLayout,
+) -> anyhow::Result<Vec<u8>> {
+    SuiJsonValue::new(value)?
+        .to_bcs_bytes(layout)
+        .map_err(|e| anyhow!("Invalid value for field `{name}`: {e}"))
+}
+
+fn is_uid_field(layout: &MoveTypeLayout) -> bool {
+    matches!(layout, MoveTypeLayout::Struct(s) if s.type_ == UID::type_())
+}
@@ -1,22 +1,47 @@
 use anyhow::anyhow;
-use std::ops::{Deref, DerefMut};
+use base64::{engine::general_purpose, Engine};
+use move_package::source_package::{
+    manifest_parser::parse_move_manifest_from_file,
+    parsed_manifest::{Dependency, DependencyKind, InternalDependency},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+use sui_json_rpc_types::ObjectChange;
+use sui_move_build::BuildConfig;
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     gas_coin::MIST_PER_SUI,
+    move_package::{MovePackage, UpgradePolicy},
     programmable_transaction_builder::ProgrammableTransactionBuilder,
-    transaction::TransactionData,
+    transaction::{ObjectArg, ProgrammableTransaction, TransactionData},
+    SUI_FRAMEWORK_PACKAGE_ID,
 };
 
 use move_binary_format::{
+    file_format::AddressIdentifierIndex,
     normalized::{Module, NoPool},
     CompiledModule,
 };
-use move_core_types::language_storage::ModuleId;
+use move_core_types::{
+    account_address::AccountAddress, ident_str, identifier::Identifier, language_storage::ModuleId,
+};
 
-use sui_json_rpc_types::{SuiMoveNormalizedFunction, SuiTransactionBlockResponse};
+use sui_json_rpc_types::{
+    MoveFunctionArgType, ObjectValueKind, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiMoveNormalizedStruct, SuiMoveNormalizedType, SuiTransactionBlockResponse,
+};
 
 use crate::{
-    sandbox::{AuthMode, CoinExtension, MoveVMSandbox},
+    sandbox::{
+        engines::verification::{self, PublishVerificationError},
+        AuthMode, CoinExtension, MoveVMSandbox, StackTraceExtension,
+    },
+    types::{
+        PublishDiagnostic, PublishFromPathResult, PublishOptions, ResolvedPackage, SourceFile,
+    },
     utils::{parse_account_address, parse_identifier, parse_object_id},
 };
 
@@ -42,35 +67,208 @@ where
         module_id: String,
         function_id: String,
     ) -> anyhow::Result<SuiMoveNormalizedFunction> {
-        let object_id = parse_object_id(&package_id)?;
+        let module = self.normalized_module(&package_id, &module_id)?;
+
+        let function = module
+            .functions
+            .get(&parse_identifier(&function_id)?)
+            .ok_or(anyhow!("Function {function_id} not found in module"))?;
+
+        Ok(SuiMoveNormalizedFunction::from(&**function))
+    }
+
+    pub fn get_normalized_move_struct(
+        &self,
+        package_id: String,
+        module_id: String,
+        struct_id: String,
+    ) -> anyhow::Result<SuiMoveNormalizedStruct> {
+        let module = self.normalized_module(&package_id, &module_id)?;
+
+        let structure = module
+            .structs
+            .get(&parse_identifier(&struct_id)?)
+            .ok_or(anyhow!("Struct {struct_id} not found in module"))?;
+
+        Ok(SuiMoveNormalizedStruct::from(&**structure))
+    }
+
+    pub fn get_normalized_move_module(
+        &self,
+        package_id: String,
+        module_id: String,
+    ) -> anyhow::Result<SuiMoveNormalizedModule> {
+        let module = self.normalized_module(&package_id, &module_id)?;
+
+        Ok(SuiMoveNormalizedModule::from(&module))
+    }
+
+    pub fn get_normalized_move_modules_by_package(
+        &self,
+        package_id: String,
+    ) -> anyhow::Result<BTreeMap<String, SuiMoveNormalizedModule>> {
+        let package = self.move_package(&package_id)?;
+
+        package
+            .serialized_module_map()
+            .iter()
+            .map(|(name, bytes)| {
+                let module = CompiledModule::deserialize_with_defaults(bytes)?;
+                let module = Module::new(&mut NoPool, &module, false);
+
+                Ok((name.clone(), SuiMoveNormalizedModule::from(&module)))
+            })
+            .collect()
+    }
+
+    // mirrors the fullnode classification of parameters used by the TS SDK transaction builder
+    pub fn get_move_function_arg_types(
+        &self,
+        package_id: String,
+        module_id: String,
+        function_id: String,
+    ) -> anyhow::Result<Vec<MoveFunctionArgType>> {
+        let function = self.get_normalized_move_function(package_id, module_id, function_id)?;
+
+        Ok(function
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                SuiMoveNormalizedType::Struct { .. } => {
+                    MoveFunctionArgType::Object(ObjectValueKind::ByValue)
+                }
+                SuiMoveNormalizedType::Reference(_) => {
+                    MoveFunctionArgType::Object(ObjectValueKind::ByImmutableReference)
+                }
+                SuiMoveNormalizedType::MutableReference(_) => {
+                    MoveFunctionArgType::Object(ObjectValueKind::ByMutableReference)
+                }
+                _ => MoveFunctionArgType::Pure,
+            })
+            .collect())
+    }
+
+    pub fn verify_package(
+        &self,
+        modules: &[Vec<u8>],
+        dep_ids: &[ObjectID],
+    ) -> anyhow::Result<Vec<PublishDiagnostic>> {
+        verification::verify_package(
+            modules,
+            dep_ids,
+            self.sandbox.storage().as_inner(),
+            &self.sandbox.config,
+        )
+    }
+
+    fn ensure_verified(&self, modules: &[Vec<u8>], dep_ids: &[ObjectID]) -> anyhow::Result<()> {
+        let diagnostics = self.verify_package(modules, dep_ids)?;
+
+        if !diagnostics.is_empty() {
+            return Err(PublishVerificationError { diagnostics }.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn resolve_package(&self, name: &str) -> anyhow::Result<ResolvedPackage> {
+        let registry = self.sandbox.storage().package_registry();
+        let original_id = registry
+            .original_id(name)
+            .ok_or(anyhow!("No package registered as `{name}`"))?;
+
+        Ok(ResolvedPackage {
+            name: name.to_string(),
+            original_id,
+            latest_id: registry.latest(&original_id).unwrap_or(original_id),
+            versions: registry.versions(&original_id),
+        })
+    }
+
+    // replaces registered package names in a type string with the address that defines the
+    // type, e.g. `my_pkg::pool::Pool<0x2::sui::SUI>` -> `0x...::pool::Pool<0x2::sui::SUI>`
+    pub fn expand_type(&self, type_str: &str) -> anyhow::Result<String> {
+        let mut expanded = String::with_capacity(type_str.len());
+        let mut token = String::new();
+
+        for ch in type_str.chars() {
+            if matches!(ch, '<' | '>' | ',') || ch.is_whitespace() {
+                expanded.push_str(&self.expand_type_token(&token)?);
+                expanded.push(ch);
+                token.clear();
+            } else {
+                token.push(ch);
+            }
+        }
+
+        expanded.push_str(&self.expand_type_token(&token)?);
+
+        Ok(expanded)
+    }
+
+    fn expand_type_token(&self, token: &str) -> anyhow::Result<String> {
+        let mut parts = token.splitn(3, "::");
+
+        let (Some(name), Some(module)) = (parts.next(), parts.next()) else {
+            return Ok(token.to_string());
+        };
+
+        let registry = self.sandbox.storage().package_registry();
+        let Some(original_id) = registry.original_id(name) else {
+            return Ok(token.to_string());
+        };
+
+        let Some(struct_name) = parts.next() else {
+            return Ok(format!("{}::{module}", original_id.to_hex_literal()));
+        };
+
+        // types keep the address of the package version that introduced them
+        let latest_id = registry.latest(&original_id).unwrap_or(original_id);
+        let defining_id = self
+            .move_package(&latest_id.to_hex_literal())?
+            .type_origin_map()
+            .get(&(module.to_string(), struct_name.to_string()))
+            .copied()
+            .unwrap_or(original_id);
+
+        Ok(format!(
+            "{}::{module}::{struct_name}",
+            defining_id.to_hex_literal()
+        ))
+    }
+
+    fn move_package(&self, package_id: &str) -> anyhow::Result<&MovePackage> {
+        let object_id = parse_object_id(package_id)?;
         let package = self
             .sandbox
             .storage()
             .get_object(&object_id)
             .ok_or(anyhow!("No object: {package_id}"))?;
 
-        let package = package
+        package
             .as_inner()
             .data
             .try_as_package()
-            .ok_or(anyhow!("Object {} is not a package", package.id()))?;
+            .ok_or(anyhow!("Object {} is not a package", package.id()))
+    }
+
+    fn normalized_module(
+        &self,
+        package_id: &str,
+        module_id: &str,
+    ) -> anyhow::Result<Module<Identifier>> {
+        let package = self.move_package(package_id)?;
 
         let module = package
             .get_module(&ModuleId::new(
-                parse_account_address(&package_id)?,
-                parse_identifier(&module_id)?,
+                parse_account_address(package_id)?,
+                parse_identifier(module_id)?,
             ))
             .ok_or(anyhow!("Module {module_id} not found in package"))?;
 
         let module = CompiledModule::deserialize_with_defaults(module)?;
-        let module = Module::new(&mut NoPool, &module, false);
-
-        let function = module
-            .functions
-            .get(&parse_identifier(&function_id)?)
-            .ok_or(anyhow!("Function {function_id} not found in module"))?;
 
-        Ok(SuiMoveNormalizedFunction::from(&**function))
+        Ok(Module::new(&mut NoPool, &module, false))
     }
 }
 
@@ -84,36 +282,408 @@ where
         modules: Vec<Vec<u8>>,
         dep_ids: Vec<ObjectID>,
     ) -> anyhow::Result<SuiTransactionBlockResponse> {
-        let tx = {
+        self.publish_package_with_options(sender, modules, dep_ids, &PublishOptions::default())
+    }
+
+    pub fn publish_package_with_options(
+        &mut self,
+        sender: SuiAddress,
+        modules: Vec<Vec<u8>>,
+        dep_ids: Vec<ObjectID>,
+        options: &PublishOptions,
+    ) -> anyhow::Result<SuiTransactionBlockResponse> {
+        let tx = self.build_publish_transaction(sender, modules, dep_ids, options)?;
+        let response = self.execute_without_auth(tx)?;
+
+        self.record_published_package(&response, options)?;
+
+        Ok(response)
+    }
+
+    // registers the name and keeps the sources of the package version the transaction created
+    fn record_published_package(
+        &mut self,
+        response: &SuiTransactionBlockResponse,
+        options: &PublishOptions,
+    ) -> anyhow::Result<()> {
+        if let Some(package_id) = published_package_id(response) {
+            if let Some(name) = &options.name {
+                self.register_package(name.clone(), package_id)?;
+            }
+
+            let source_maps = options
+                .source_maps
+                .iter()
+                .map(|source_map| {
+                    Ok((
+                        Identifier::new(source_map.module.as_str())?,
+                        bcs::from_bytes(
+                            &general_purpose::STANDARD.decode(&source_map.source_map)?,
+                        )?,
+                    ))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            self.sandbox.storage_mut().add_package_sources(
+                package_id,
+                source_maps,
+                &options.sources,
+            );
+        }
+
+        Ok(())
+    }
+
+    // names point at the original package id, so they keep resolving after upgrades
+    pub fn register_package(&mut self, name: String, package_id: ObjectID) -> anyhow::Result<()> {
+        let storage = self.sandbox.storage();
+
+        if storage
+            .get_object(&package_id)
+            .and_then(|object| object.data.try_as_package())
+            .is_none()
+        {
+            anyhow::bail!("Object {package_id} is not a package");
+        }
+
+        let original_id = storage.original_package_id(&package_id);
+
+        self.sandbox
+            .storage_mut()
+            .package_registry_mut()
+            .register_name(name, original_id);
+
+        Ok(())
+    }
+
+    // the returned transaction is meant to be signed and executed like any other one,
+    // so publishing goes through the same signature checks as a real deploy
+    pub fn build_publish_transaction(
+        &mut self,
+        sender: SuiAddress,
+        modules: Vec<Vec<u8>>,
+        dep_ids: Vec<ObjectID>,
+        options: &PublishOptions,
+    ) -> anyhow::Result<TransactionData> {
+        self.ensure_verified(&modules, &dep_ids)?;
+
+        let pt = {
             let mut builder = ProgrammableTransactionBuilder::new();
 
-            let upgrade_cap = builder.publish_upgradeable(modules, dep_ids);
-            builder.transfer_arg(sender, upgrade_cap);
+            if options.upgradeable {
+                let upgrade_cap = builder.publish_upgradeable(modules, dep_ids);
+                builder.transfer_arg(sender, upgrade_cap);
+            } else {
+                builder.publish_immutable(modules, dep_ids);
+            }
+
+            builder.finish()
+        };
 
-            let pt = builder.finish();
-            let balance = self.sandbox.storage().calculate_balance(sender, None);
+        self.build_transaction(sender, pt, options)
+    }
 
-            if balance < OVERLY_SAFE_SUI_BALANCE {
-                self.sandbox
-                    .storage_mut()
-                    .mint_gas_coin(sender, OVERLY_SAFE_SUI_BALANCE);
+    // compiles the package in-process; named addresses the manifests leave as `_` are
+    // provided from the packages published earlier with `publish_from_path`
+    pub fn publish_from_path(
+        &mut self,
+        sender: SuiAddress,
+        path: &Path,
+    ) -> anyhow::Result<PublishFromPathResult> {
+        let unresolved = unresolved_named_addresses(path)?;
+
+        let mut config = BuildConfig::default();
+        config.config.skip_fetch_latest_git_deps = true;
+        config.config.additional_named_addresses = self
+            .sandbox
+            .storage()
+            .package_registry()
+            .names()
+            .iter()
+            .filter(|(name, _)| unresolved.contains(*name))
+            .map(|(name, id)| (name.clone(), (*id).into()))
+            .collect();
+
+        let compiled = config.build(path)?;
+
+        let mut root_modules = compiled.get_dependency_sorted_modules(false);
+        let root_names = root_modules
+            .iter()
+            .map(|module| module.name().to_owned())
+            .collect::<BTreeSet<_>>();
+        let mut dep_ids = compiled.get_dependency_storage_package_ids();
+        let mut linked = BTreeMap::new();
+
+        // local dependencies without a `published-at` are resolved against the packages
+        // published into the sandbox under the same name, this includes the dependencies
+        // of dependencies. their manifests pin them to 0x0, which the build doesn't let
+        // `additional_named_addresses` override, so they're linked after compiling
+        for name in &compiled.dependency_ids.unpublished {
+            let registry = self.sandbox.storage().package_registry();
+            let original_id = registry
+                .original_id(name.as_str())
+                .ok_or(anyhow!("Dependency {name} is not published in the sandbox"))?;
+            let latest_id = registry.latest(&original_id).unwrap_or(original_id);
+
+            let dependency = compiled
+                .package
+                .deps_compiled_units
+                .iter()
+                .filter(|(package, _)| package == name)
+                .map(|(_, unit)| &unit.unit.module)
+                .collect::<Vec<_>>();
+
+            for module in &mut root_modules {
+                link_dependency(module, &dependency, &root_names, original_id);
+            }
+
+            dep_ids.push(latest_id);
+            linked.insert(name.to_string(), original_id);
+        }
+
+        let modules = root_modules
+            .iter()
+            .map(|module| {
+                let mut bytes = vec![];
+                module.serialize_with_version(module.version, &mut bytes)?;
+
+                Ok(bytes)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let response = self.publish_package(sender, modules, dep_ids)?;
+
+        if !response.errors.is_empty() {
+            anyhow::bail!("{}", response.errors.join(", "));
+        }
+
+        let package_id = published_package_id(&response)
+            .ok_or(anyhow!("Publish transaction did not create a package"))?;
+
+        let named_addresses: BTreeMap<String, ObjectID> = compiled
+            .package
+            .compiled_package_info
+            .address_alias_instantiation
+            .iter()
+            .map(|(name, address)| {
+                let id = match ObjectID::from(*address) {
+                    id if id == ObjectID::ZERO => {
+                        linked.get(name.as_str()).copied().unwrap_or(package_id)
+                    }
+                    id => id,
+                };
+
+                (name.to_string(), id)
+            })
+            .collect();
+
+        self.register_package(
+            compiled
+                .package
+                .compiled_package_info
+                .package_name
+                .to_string(),
+            package_id,
+        )?;
+
+        for (name, id) in &named_addresses {
+            if *id == package_id {
+                self.register_package(name.clone(), package_id)?;
             }
+        }
+
+        Ok(PublishFromPathResult {
+            package_id,
+            named_addresses,
+            response,
+        })
+    }
+
+    pub fn upgrade_package(
+        &mut self,
+        package_id: ObjectID,
+        upgrade_cap_id: ObjectID,
+        modules: Vec<Vec<u8>>,
+        dep_ids: Vec<ObjectID>,
+        policy: UpgradePolicy,
+        options: &PublishOptions,
+    ) -> anyhow::Result<SuiTransactionBlockResponse> {
+        let upgrade_cap = self
+            .sandbox
+            .storage()
+            .get_object(&upgrade_cap_id)
+            .ok_or(anyhow!("No object: {upgrade_cap_id}"))?;
+
+        let sender = upgrade_cap
+            .owner
+            .get_address_owner_address()
+            .map_err(|_| anyhow!("UpgradeCap {upgrade_cap_id} is not owned by an address"))?;
+        let upgrade_cap_ref = upgrade_cap.compute_object_reference();
+
+        self.ensure_verified(&modules, &dep_ids)?;
+
+        let digest =
+            MovePackage::compute_digest_for_modules_and_deps(&modules, &dep_ids, true).to_vec();
 
-            let balance = self.sandbox.storage().calculate_balance(sender, None);
-            let payment = self.sandbox.storage().get_default_gas_payment(sender);
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+
+            let cap = builder.obj(ObjectArg::ImmOrOwnedObject(upgrade_cap_ref))?;
+            let policy = builder.pure(policy as u8)?;
+            let digest = builder.pure(digest)?;
+
+            let ticket = builder.programmable_move_call(
+                SUI_FRAMEWORK_PACKAGE_ID,
+                ident_str!("package").to_owned(),
+                ident_str!("authorize_upgrade").to_owned(),
+                vec![],
+                vec![cap, policy, digest],
+            );
+            let receipt = builder.upgrade(package_id, ticket, dep_ids, modules);
 
-            TransactionData::new_programmable(
-                sender,
-                payment,
-                pt,
-                balance,
-                self.sandbox.reference_price,
-            )
+            builder.programmable_move_call(
+                SUI_FRAMEWORK_PACKAGE_ID,
+                ident_str!("package").to_owned(),
+                ident_str!("commit_upgrade").to_owned(),
+                vec![],
+                vec![cap, receipt],
+            );
+
+            builder.finish()
         };
 
+        let tx = self.build_transaction(sender, pt, options)?;
+        let response = self.execute_without_auth(tx)?;
+
+        self.record_published_package(&response, options)?;
+
+        Ok(response)
+    }
+
+    fn build_transaction(
+        &mut self,
+        sender: SuiAddress,
+        pt: ProgrammableTransaction,
+        options: &PublishOptions,
+    ) -> anyhow::Result<TransactionData> {
+        let needs_payment = self.sandbox.gas_mode.needs_payment();
+        let balance = self.sandbox.storage().calculate_balance(sender, None);
+
+        if needs_payment && options.auto_fund && balance < OVERLY_SAFE_SUI_BALANCE {
+            self.sandbox
+                .storage_mut()
+                .mint_gas_coin(sender, OVERLY_SAFE_SUI_BALANCE);
+        }
+
+        let balance = self.sandbox.storage().calculate_balance(sender, None);
+        let payment = self.sandbox.storage().get_default_gas_payment(sender);
+
+        if needs_payment && payment.is_empty() {
+            anyhow::bail!("Sender {sender} has no SUI coins to pay for gas");
+        }
+
+        let max_budget = self.sandbox.config.max_tx_gas();
+        let default_budget = if needs_payment {
+            balance.min(max_budget)
+        } else {
+            max_budget
+        };
+
+        Ok(TransactionData::new_programmable(
+            sender,
+            payment,
+            pt,
+            options.gas_budget.unwrap_or(default_budget),
+            self.sandbox.reference_price,
+        ))
+    }
+
+    fn execute_without_auth(
+        &mut self,
+        tx: TransactionData,
+    ) -> anyhow::Result<SuiTransactionBlockResponse> {
         self.sandbox.execute_with_auth_override(
             |this| this.transaction_mut().execute_function(tx, vec![]),
             AuthMode::Disabled,
         )
     }
 }
+
+// named addresses without a value in the manifest of the package or of its local dependencies
+fn unresolved_named_addresses(path: &Path) -> anyhow::Result<BTreeSet<String>> {
+    let mut unresolved = BTreeSet::new();
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(path) = pending.pop() {
+        if !visited.insert(path.canonicalize()?) {
+            continue;
+        }
+
+        let manifest = parse_move_manifest_from_file(&path)?;
+
+        for (name, address) in manifest.addresses.unwrap_or_default() {
+            if address.is_none() {
+                unresolved.insert(name.to_string());
+            }
+        }
+
+        for dependency in manifest.dependencies.into_values() {
+            if let Dependency::Internal(InternalDependency {
+                kind: DependencyKind::Local(local),
+                ..
+            }) = dependency
+            {
+                pending.push(path.join(local));
+            }
+        }
+    }
+
+    Ok(unresolved)
+}
+
+// dependencies compiled at 0x0 are referenced through module handles at 0x0, these are
+// pointed at the package the sandbox published the dependency as. the root package is
+// compiled at 0x0 too, so handles to its own modules are never rewritten
+fn link_dependency(
+    module: &mut CompiledModule,
+    dependency: &[&CompiledModule],
+    root_names: &BTreeSet<Identifier>,
+    original_id: ObjectID,
+) {
+    let names = dependency
+        .iter()
+        .filter(|dep| *dep.address() == AccountAddress::ZERO)
+        .map(|dep| dep.name().to_owned())
+        .filter(|name| !root_names.contains(name))
+        .collect::<BTreeSet<_>>();
+
+    let address = AddressIdentifierIndex(module.address_identifiers.len() as u16);
+    let self_handle = module.self_handle_idx().0 as usize;
+    let mut linked = false;
+
+    for (index, handle) in module.module_handles.iter_mut().enumerate() {
+        if index != self_handle
+            && module.address_identifiers[handle.address.0 as usize] == AccountAddress::ZERO
+            && names.contains(&module.identifiers[handle.name.0 as usize])
+        {
+            handle.address = address;
+            linked = true;
+        }
+    }
+
+    if linked {
+        module.address_identifiers.push(original_id.into());
+    }
+}
+
+fn published_package_id(response: &SuiTransactionBlockResponse) -> Option<ObjectID> {
+    response
+        .object_changes
+        .iter()
+        .flatten()
+        .find_map(|change| match change {
+            ObjectChange::Published { package_id, .. } => Some(*package_id),
+            _ => None,
+        })
+}
@@ -1,5 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
+use move_trace_format::format::MoveTrace;
 use sui_json_rpc_types::{
     BalanceChange, DryRunTransactionBlockResponse, ObjectChange, SuiTransactionBlock,
     SuiTransactionBlockData, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
@@ -8,6 +9,7 @@ use sui_json_rpc_types::{
 use sui_types::{
     base_types::ObjectID,
     crypto::Signature,
+    digests::TransactionDigest,
     effects::TransactionEffects,
     transaction::{Transaction, TransactionData, TransactionDataAPI},
 };
@@ -18,11 +20,11 @@ use crate::{
         transaction_pipeline::{
             stages::{
                 DryRunStage, EffectsStage, ExecutionStage, StorageStage, ValidationInput,
-                ValidationStage,
+                ValidationMode, ValidationStage,
             },
             Pipeline, PipelineResult, TransactionStage,
         },
-        CoinExtension, MoveVMSandbox,
+        CoinExtension, MoveVMSandbox, OrderingStrategy,
     },
     utils::serialize_bcs,
 };
@@ -108,38 +110,142 @@ where
         tx_data: TransactionData,
         signatures: Vec<Signature>,
     ) -> anyhow::Result<SuiTransactionBlockResponse> {
+        let (response, _) = self.execute(tx_data, signatures, false)?;
+
+        Ok(response)
+    }
+
+    // same as `execute_function`, also returning the Move trace of every executed instruction
+    // (frames, locals, stack effects and remaining gas); there is no trace when the transaction
+    // was rejected before execution
+    pub fn execute_function_traced(
+        &mut self,
+        tx_data: TransactionData,
+        signatures: Vec<Signature>,
+    ) -> anyhow::Result<(SuiTransactionBlockResponse, Option<MoveTrace>)> {
+        self.execute(tx_data, signatures, true)
+    }
+
+    fn execute(
+        &mut self,
+        tx_data: TransactionData,
+        signatures: Vec<Signature>,
+        trace: bool,
+    ) -> anyhow::Result<(SuiTransactionBlockResponse, Option<MoveTrace>)> {
         let pipeline = Pipeline::new(ValidationStage)
             .then(ExecutionStage)
             .then(EffectsStage)
             .then(StorageStage);
 
+        let digest = tx_data.digest();
         let input = ValidationInput {
-            tx_data: tx_data.clone(),
+            tx_data,
             signatures,
+            mode: ValidationMode::Execution,
+            trace,
         };
 
-        let response = match pipeline.execute(input, &mut self.sandbox)? {
-            PipelineResult::Continue(output) => self.create_transaction_response(
-                &output.tx_data,
-                Some(output.transaction),
-                output.effects,
-                output.events,
-                output.object_changes,
-                output.balance_changes,
-                output
-                    .execution_result
-                    .err()
-                    .map(|e| vec![e.to_string()])
-                    .unwrap_or_default(),
+        // locks taken by an earlier lock or submit of the same transaction outlive this call
+        let held = self.sandbox.object_locks.held_by(&digest);
+        let result = pipeline.execute(input, &mut self.sandbox);
+
+        // a transaction that wasn't executed doesn't consume the versions it locked, whichever
+        // stage stopped it
+        if !matches!(result, Ok(PipelineResult::Continue(_))) {
+            self.sandbox.object_locks.release_except(&digest, &held);
+        }
+
+        let (response, trace) = match result? {
+            PipelineResult::Continue(output) => (
+                self.create_transaction_response(
+                    &output.tx_data,
+                    Some(output.transaction),
+                    output.effects,
+                    output.events,
+                    output.object_changes,
+                    output.balance_changes,
+                    output
+                        .execution_result
+                        .err()
+                        .map(|e| vec![e.to_string()])
+                        .unwrap_or_default(),
+                )?,
+                output.trace,
             ),
-            PipelineResult::EarlyReturn(response) => Ok(response),
-        }?;
+            PipelineResult::EarlyReturn(response) => (response, None),
+        };
 
         self.sandbox
             .storage
             .insert_transaction(response.digest, response.clone());
 
-        Ok(response)
+        Ok((response, trace))
+    }
+
+    // validates the transaction and locks its owned inputs without executing it, like a
+    // validator signing it; any other transaction using the same versions is rejected until
+    // this one is executed
+    pub fn lock_transaction(
+        &mut self,
+        tx_data: TransactionData,
+        signatures: Vec<Signature>,
+    ) -> anyhow::Result<TransactionDigest> {
+        let input = ValidationInput {
+            tx_data: tx_data.clone(),
+            signatures,
+            mode: ValidationMode::Locking,
+            trace: false,
+        };
+
+        match ValidationStage.execute(input, &mut self.sandbox)? {
+            PipelineResult::Continue(_) => Ok(tx_data.digest()),
+            PipelineResult::EarlyReturn(response) => {
+                anyhow::bail!("{}", response.errors.join(", "))
+            }
+        }
+    }
+
+    // locks the owned inputs like `lock_transaction` and queues the transaction until the next
+    // batch is sealed
+    pub fn submit_transaction(
+        &mut self,
+        tx_data: TransactionData,
+        signatures: Vec<Signature>,
+    ) -> anyhow::Result<TransactionDigest> {
+        let digest = self.lock_transaction(tx_data.clone(), signatures.clone())?;
+
+        self.sandbox.mempool.submit(tx_data, signatures);
+
+        Ok(digest)
+    }
+
+    // executes the queued transactions in the order picked by `strategy`, a sealed batch ends
+    // the current checkpoint; a transaction that fails to execute doesn't stop the batch, it goes
+    // back to the mempool keeping the locks it was submitted with
+    pub fn seal_batch(
+        &mut self,
+        strategy: OrderingStrategy,
+    ) -> Vec<(
+        TransactionDigest,
+        anyhow::Result<SuiTransactionBlockResponse>,
+    )> {
+        let batch = self.sandbox.mempool.seal(strategy);
+        let mut results = vec![];
+
+        for tx in batch {
+            let digest = tx.tx_data.digest();
+            let result = self.execute_function(tx.tx_data.clone(), tx.signatures.clone());
+
+            if result.is_err() {
+                self.sandbox.mempool.submit(tx.tx_data, tx.signatures);
+            }
+
+            results.push((digest, result));
+        }
+
+        self.sandbox.storage_mut().bump_checkpoint();
+
+        results
     }
 
     pub fn dry_run_transaction(
@@ -178,6 +284,20 @@ where
             tx_data.gas_data_mut().payment = vec![reference];
 
             Some(coin_for_dry_run)
+        } else if let Some(coin) = self
+            .sandbox
+            .gas_mode
+            .faucet_coin(tx_data.gas_data(), &self.sandbox.storage)
+        {
+            // the validation stage leaves the faucet coin of a dry run unstored
+            let id = coin.id();
+            tx_data
+                .gas_data_mut()
+                .payment
+                .push(coin.compute_object_reference());
+            self.sandbox.storage_mut().insert_object(coin);
+
+            Some(id)
         } else {
             None
         };
@@ -185,6 +305,8 @@ where
         let input = ValidationInput {
             tx_data: tx_data.clone(),
             signatures: vec![],
+            mode: ValidationMode::DryRun,
+            trace: false,
         };
 
         let execution_result = self.sandbox.execute_with_auth_override(
@@ -0,0 +1,224 @@
+use std::{collections::BTreeMap, fmt};
+
+use move_binary_format::{
+    errors::{Location, VMError},
+    file_format::TableIndex,
+    CompiledModule, IndexKind,
+};
+use move_core_types::vm_status::StatusCode;
+use sui_types::{
+    base_types::ObjectID, error::ExecutionError, storage::BackingPackageStore,
+    supported_protocol_versions::ProtocolConfig,
+};
+
+use crate::types::PublishDiagnostic;
+
+#[derive(Debug)]
+pub struct PublishVerificationError {
+    pub diagnostics: Vec<PublishDiagnostic>,
+}
+
+impl fmt::Display for PublishVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                let location = match (&d.module, &d.function) {
+                    (Some(module), Some(function)) => format!("{module}::{function}"),
+                    (Some(module), None) => module.clone(),
+                    _ => "package".to_string(),
+                };
+
+                let mut line = format!("{location}: {} check failed with {}", d.check, d.status);
+
+                if let Some(code) = d.status_code {
+                    line.push_str(&format!(" ({code})"));
+                }
+                if let Some(offset) = d.code_offset {
+                    line.push_str(&format!(" at code offset {offset}"));
+                }
+                if let Some(message) = &d.message {
+                    line.push_str(&format!(": {message}"));
+                }
+
+                line
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for PublishVerificationError {}
+
+// runs the checks a validator does while publishing, module by module, so that a failure can be
+// reported with the module, function and check it came from
+pub fn verify_package(
+    modules: &[Vec<u8>],
+    dep_ids: &[ObjectID],
+    store: &dyn BackingPackageStore,
+    config: &ProtocolConfig,
+) -> anyhow::Result<Vec<PublishDiagnostic>> {
+    let mut compiled = vec![];
+    let mut diagnostics = vec![];
+
+    for bytes in modules {
+        match CompiledModule::deserialize_with_defaults(bytes) {
+            Ok(module) => compiled.push(module),
+            Err(e) => diagnostics.push(from_vm_error(
+                "deserialization",
+                None,
+                &e.finish(Location::Undefined),
+            )),
+        }
+    }
+
+    if !diagnostics.is_empty() {
+        return Ok(diagnostics);
+    }
+
+    let dependencies = dependency_modules(dep_ids, store)?;
+    let verifier_config = config.verifier_config(None);
+
+    for module in &compiled {
+        let result =
+            move_bytecode_verifier::verify_module_with_config_unmetered(&verifier_config, module)
+                .map_err(|e| from_vm_error(bytecode_check(e.major_status()), Some(module), &e))
+                .and_then(|_| {
+                    move_bytecode_verifier::dependencies::verify_module(
+                        module,
+                        dependencies.iter().chain(compiled.iter()),
+                    )
+                    .map_err(|e| from_vm_error("linkage", Some(module), &e))
+                })
+                .and_then(|_| {
+                    sui_verifier::verifier::sui_verify_module_unmetered(
+                        module,
+                        &BTreeMap::new(),
+                        &verifier_config,
+                    )
+                    .map_err(|e| from_execution_error(module, &e))
+                });
+
+        if let Err(diagnostic) = result {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+fn dependency_modules(
+    dep_ids: &[ObjectID],
+    store: &dyn BackingPackageStore,
+) -> anyhow::Result<Vec<CompiledModule>> {
+    let mut modules = vec![];
+
+    for dep_id in dep_ids {
+        let package = store
+            .get_package_object(dep_id)?
+            .ok_or(anyhow::anyhow!("Dependency {dep_id} not found"))?;
+
+        for bytes in package.move_package().serialized_module_map().values() {
+            modules.push(CompiledModule::deserialize_with_defaults(bytes)?);
+        }
+    }
+
+    Ok(modules)
+}
+
+fn from_vm_error(
+    check: &str,
+    module: Option<&CompiledModule>,
+    error: &VMError,
+) -> PublishDiagnostic {
+    let function_index = error
+        .offsets()
+        .first()
+        .map(|(index, _)| index.0)
+        .or_else(|| {
+            error
+                .indices()
+                .iter()
+                .find(|(kind, _)| *kind == IndexKind::FunctionDefinition)
+                .map(|(_, index)| *index)
+        });
+
+    let module_name = module
+        .map(|m| m.self_id().name().to_string())
+        .or(match error.location() {
+            Location::Module(id) => Some(id.name().to_string()),
+            Location::Undefined => None,
+        });
+
+    PublishDiagnostic {
+        module: module_name,
+        function: module
+            .zip(function_index)
+            .and_then(|(module, index)| function_name(module, index)),
+        check: check.to_string(),
+        status: format!("{:?}", error.major_status()),
+        status_code: Some(error.major_status() as u64),
+        code_offset: error.offsets().first().map(|(_, offset)| *offset),
+        message: error.message().cloned(),
+    }
+}
+
+fn from_execution_error(module: &CompiledModule, error: &ExecutionError) -> PublishDiagnostic {
+    PublishDiagnostic {
+        module: Some(module.self_id().name().to_string()),
+        function: None,
+        check: "sui".to_string(),
+        status: format!("{:?}", error.kind()),
+        status_code: None,
+        code_offset: None,
+        message: error.source().as_ref().map(|source| source.to_string()),
+    }
+}
+
+fn function_name(module: &CompiledModule, index: TableIndex) -> Option<String> {
+    let definition = module.function_defs().get(index as usize)?;
+    let handle = module.function_handle_at(definition.function);
+
+    Some(module.identifier_at(handle.name).to_string())
+}
+
+// groups the status codes of the bytecode verifier by the pass that reports them
+fn bytecode_check(status: StatusCode) -> &'static str {
+    use StatusCode::*;
+
+    match status {
+        BORROWLOC_REFERENCE_ERROR
+        | BORROWLOC_UNAVAILABLE_ERROR
+        | BORROWLOC_EXISTS_BORROW_ERROR
+        | BORROWFIELD_EXISTS_MUTABLE_BORROW_ERROR
+        | COPYLOC_EXISTS_BORROW_ERROR
+        | MOVELOC_EXISTS_BORROW_ERROR
+        | READREF_EXISTS_MUTABLE_BORROW_ERROR
+        | WRITEREF_EXISTS_BORROW_ERROR
+        | FREEZEREF_EXISTS_MUTABLE_BORROW_ERROR
+        | CALL_BORROWED_MUTABLE_REFERENCE_ERROR
+        | RET_BORROWED_MUTABLE_REFERENCE_ERROR
+        | UNSAFE_RET_LOCAL_OR_RESOURCE_STILL_BORROWED => "reference safety",
+        CONSTRAINT_NOT_SATISFIED
+        | FIELD_MISSING_TYPE_ABILITY
+        | POP_WITHOUT_DROP_ABILITY
+        | WRITEREF_WITHOUT_DROP_ABILITY
+        | COPYLOC_WITHOUT_COPY_ABILITY
+        | READREF_WITHOUT_COPY_ABILITY
+        | UNSAFE_RET_UNUSED_VALUES_WITHOUT_DROP
+        | INVALID_PHANTOM_TYPE_PARAM_POSITION => "ability",
+        MOVELOC_UNAVAILABLE_ERROR | COPYLOC_UNAVAILABLE_ERROR | STLOC_UNSAFE_TO_DESTROY_ERROR => {
+            "locals safety"
+        }
+        NEGATIVE_STACK_SIZE_WITHIN_BLOCK | POSITIVE_STACK_SIZE_AT_BLOCK_END => "stack usage",
+        INDEX_OUT_OF_BOUNDS => "bounds",
+        DUPLICATE_ELEMENT => "duplication",
+        RECURSIVE_STRUCT_DEFINITION | LOOP_IN_INSTANTIATION_GRAPH => "recursion",
+        INVALID_FALL_THROUGH | INVALID_LOOP_SPLIT | INVALID_LOOP_BREAK | INVALID_LOOP_CONTINUE => {
+            "control flow"
+        }
+        _ => "bytecode",
+    }
+}
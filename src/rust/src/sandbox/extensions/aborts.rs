@@ -0,0 +1,85 @@
+use move_binary_format::{file_format::SignatureToken, CompiledModule};
+use move_core_types::error_bitset::ErrorBitset;
+use sui_types::{
+    base_types::ObjectID,
+    effects::{TransactionEffects, TransactionEffectsAPI},
+    execution_status::{ExecutionFailureStatus, ExecutionStatus},
+};
+
+use crate::{
+    sandbox::{extensions::stack_traces::StackTraceExtension, storage::StorageExtension},
+    types::MoveAbort,
+};
+
+pub trait AbortExtension {
+    fn decode_move_abort(&self, effects: &TransactionEffects) -> Option<MoveAbort>;
+}
+
+impl AbortExtension for StorageExtension {
+    fn decode_move_abort(&self, effects: &TransactionEffects) -> Option<MoveAbort> {
+        let ExecutionStatus::Failure {
+            error: ExecutionFailureStatus::MoveAbort(location, code),
+            ..
+        } = effects.status()
+        else {
+            return None;
+        };
+
+        // decoded against the package version the abort location points at
+        let package = ObjectID::from(*location.module.address());
+        let module = self.module_at(&location.module);
+
+        let function = location.function_name.clone().or_else(|| {
+            module
+                .as_ref()
+                .and_then(|module| function_name(module, location.function))
+        });
+
+        let mut abort = MoveAbort {
+            package,
+            module: location.module.name().to_string(),
+            function,
+            instruction: location.instruction,
+            code: *code,
+            error_name: None,
+            line: None,
+            message: None,
+            stack: self.stack_trace(effects.transaction_digest()),
+        };
+
+        if let (Some(module), Some(error)) = (module, ErrorBitset::from_u64(*code)) {
+            abort.line = error.line_number();
+            abort.error_name = error
+                .identifier_index()
+                .and_then(|index| module.identifiers().get(index as usize))
+                .map(|name| name.to_string());
+            abort.message = error
+                .constant_index()
+                .and_then(|index| constant_message(&module, index));
+        }
+
+        Some(abort)
+    }
+}
+
+fn function_name(module: &CompiledModule, index: u16) -> Option<String> {
+    let definition = module.function_defs().get(index as usize)?;
+    let handle = module.function_handle_at(definition.function);
+
+    Some(module.identifier_at(handle.name).to_string())
+}
+
+fn constant_message(module: &CompiledModule, index: u16) -> Option<String> {
+    let constant = module.constant_pool().get(index as usize)?;
+
+    match &constant.type_ {
+        SignatureToken::Vector(inner) if **inner == SignatureToken::U8 => {
+            let bytes: Vec<u8> = bcs::from_bytes(&constant.data).ok()?;
+
+            String::from_utf8(bytes).ok()
+        }
+        _ => constant
+            .deserialize_constant()
+            .map(|value| value.to_string()),
+    }
+}
@@ -2,6 +2,8 @@ use std::sync::Arc;
 use sui_types::{
     base_types::SuiAddress,
     crypto::{PublicKey, Signature, SuiSignature},
+    error::UserInputError,
+    object::{Object, Owner},
     signature_verification::{verify_sender_signed_data_message_signatures, VerifiedDigestCache},
     transaction::Transaction,
 };
@@ -66,6 +68,35 @@ impl AuthExtension {
         Ok(())
     }
 
+    // owned inputs have to belong to the sender and gas coins to the gas owner, which is the
+    // sponsor of a sponsored transaction
+    pub fn verify_object_owner(
+        &self,
+        object: &Object,
+        expected_owner: SuiAddress,
+        is_gas: bool,
+    ) -> Result<(), UserInputError> {
+        if self.is_disabled() {
+            return Ok(());
+        }
+
+        match &object.owner {
+            Owner::AddressOwner(owner) if *owner == expected_owner => Ok(()),
+            Owner::AddressOwner(owner) => Err(UserInputError::IncorrectUserSignature {
+                error: format!(
+                    "Object {:?} is owned by account address {:?}, but given owner/signer address is {:?}",
+                    object.id(),
+                    owner,
+                    expected_owner
+                ),
+            }),
+            owner if is_gas => Err(UserInputError::GasObjectNotOwnedObject {
+                owner: owner.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
     pub fn set_mode(&mut self, mode: AuthMode) -> AuthMode {
         let prev = self.mode;
         self.mode = mode;
@@ -5,7 +5,7 @@ use anyhow::anyhow;
 use move_core_types::language_storage::StructTag;
 use sui_json_rpc_types::{BalanceChange, ObjectChange};
 use sui_types::{
-    base_types::SuiAddress,
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
     crypto::Signature,
     effects::{TransactionEffects, TransactionEffectsAPI},
     inner_temporary_store::InnerTemporaryStore,
@@ -25,6 +25,7 @@ pub trait Changes {
         tx_data: &TransactionData,
         signatures: Option<&[Signature]>,
         auth_extension: &AuthExtension,
+        congested: &[ObjectID],
     ) -> anyhow::Result<CheckedInputObjects>;
 
     fn extract_balance_changes(
@@ -47,6 +48,7 @@ impl Changes for StorageExtension {
         tx_data: &TransactionData,
         signatures: Option<&[Signature]>,
         auth_extension: &AuthExtension,
+        congested: &[ObjectID],
     ) -> anyhow::Result<CheckedInputObjects> {
         let mut inputs = vec![];
 
@@ -56,10 +58,8 @@ impl Changes for StorageExtension {
                 .ok_or(anyhow!("Gas payment object {} not found", gas_ref.0))?
                 .clone();
             if let Some(signatures) = signatures {
-                if gas_object.owner().is_address_owned() {
-                    let address = gas_object.owner.get_address_owner_address().unwrap();
-                    auth_extension.verify_object_ownership(address, signatures)?;
-                }
+                auth_extension.verify_object_owner(&gas_object, tx_data.gas_owner(), true)?;
+                auth_extension.verify_object_ownership(tx_data.gas_owner(), signatures)?;
             }
 
             inputs.push(ObjectReadResult {
@@ -69,21 +69,42 @@ impl Changes for StorageExtension {
         }
 
         for ik in tx_data.input_objects()? {
+            // gas coins are listed among the inputs as well
+            if tx_data
+                .gas_data()
+                .payment
+                .iter()
+                .any(|gas_ref| gas_ref.0 == ik.object_id())
+            {
+                continue;
+            }
+
             let object = self
                 .get_object(&ik.object_id())
                 .ok_or(anyhow!("No object {}", ik.object_id()))?
                 .clone();
 
             if let Some(signatures) = signatures {
+                auth_extension.verify_object_owner(&object, tx_data.sender(), false)?;
+
                 if object.owner().is_address_owned() {
-                    let address = object.owner.get_address_owner_address().unwrap();
-                    auth_extension.verify_object_ownership(address, signatures)?;
+                    auth_extension.verify_object_ownership(tx_data.sender(), signatures)?;
                 }
             }
 
+            // shared objects of a transaction cancelled due to congestion can't be read
+            let read_result = match ik {
+                InputObjectKind::SharedMoveObject { id, .. } if congested.contains(&id) => {
+                    ObjectReadResultKind::CancelledTransactionSharedObject(
+                        SequenceNumber::CONGESTED,
+                    )
+                }
+                _ => ObjectReadResultKind::from(object),
+            };
+
             inputs.push(ObjectReadResult {
                 input_object_kind: ik,
-                object: ObjectReadResultKind::from(object),
+                object: read_result,
             })
         }
 
@@ -99,40 +120,44 @@ impl Changes for StorageExtension {
     ) -> Vec<BalanceChange> {
         let mut balance_changes = HashMap::new();
 
+        let mut add_change = |object: &Object, sign: i128| {
+            let Some(coin_type) = object.struct_tag().and_then(|tag| extract_coin_type(&tag))
+            else {
+                return;
+            };
+
+            let entry = balance_changes
+                .entry((object.owner.clone(), coin_type.clone()))
+                .or_insert(BalanceChange {
+                    owner: object.owner().clone(),
+                    coin_type,
+                    amount: 0,
+                });
+            entry.amount += sign * extract_coin_value(object) as i128;
+        };
+
         for created_obj in temporary_store.written.values() {
-            if !created_obj.is_coin() {
-                continue;
-            }
-            if let Some(coin_type) = extract_coin_type(&created_obj.struct_tag().unwrap()) {
-                let entry = balance_changes
-                    .entry((created_obj.owner.clone(), coin_type.clone()))
-                    .or_insert(BalanceChange {
-                        coin_type,
-                        amount: 0,
-                        owner: created_obj.owner().clone(),
-                    });
-                entry.amount += extract_coin_value(created_obj) as i128;
+            if created_obj.is_coin() {
+                add_change(created_obj, 1);
             }
         }
 
-        for (deleted_id, _) in effects.all_tombstones() {
-            let deleted_obj = match self.get_object(&deleted_id) {
-                Some(object) => object,
-                None => continue,
-            };
-            if let Some(coin_type) = extract_coin_type(&deleted_obj.struct_tag().unwrap()) {
-                let entry = balance_changes
-                    .entry((deleted_obj.owner.clone(), coin_type.clone()))
-                    .or_insert(BalanceChange {
-                        owner: deleted_obj.owner().clone(),
-                        coin_type,
-                        amount: 0,
-                    });
-                entry.amount -= extract_coin_value(deleted_obj) as i128;
+        // mutated, deleted and wrapped coins, including gas coins smashed into the first one
+        for (id, _) in effects.modified_at_versions() {
+            let previous = temporary_store
+                .input_objects
+                .get(&id)
+                .or_else(|| self.get_object(&id));
+
+            if let Some(previous) = previous {
+                add_change(previous, -1);
             }
         }
 
-        balance_changes.into_values().collect()
+        balance_changes
+            .into_values()
+            .filter(|change| change.amount != 0)
+            .collect()
     }
 
     fn compute_object_changes(
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use sui_types::{
+    base_types::ObjectID,
+    transaction::{InputObjectKind, TransactionData, TransactionDataAPI},
+};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CongestionCostMode {
+    // every transaction costs 1
+    TxCount,
+    // a transaction costs its gas budget
+    GasBudget,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CongestionConfig {
+    pub mode: CongestionCostMode,
+    // budget of every shared object per checkpoint, only mutable uses count towards it
+    pub per_object_budget: u64,
+    // how many checkpoints a transaction can be deferred by before it is cancelled
+    #[serde(default)]
+    pub max_deferral_checkpoints: u64,
+}
+
+#[derive(Default)]
+pub struct CongestionControlExtension {
+    config: Option<CongestionConfig>,
+    checkpoint: u64,
+    // cost scheduled on every object that the checkpoints sealed since haven't absorbed yet,
+    // load past one budget belongs to the checkpoints a transaction was deferred to
+    load: HashMap<ObjectID, u64>,
+}
+
+impl CongestionControlExtension {
+    pub fn set_config(&mut self, config: Option<CongestionConfig>) {
+        self.config = config;
+        self.load.clear();
+    }
+
+    // places the transaction in the first checkpoint, from the current one on, where all of its
+    // shared objects have budget left; returns the objects that would defer it past the limit,
+    // the transaction is cancelled then and doesn't add to their load
+    pub fn schedule(&mut self, tx_data: &TransactionData, checkpoint: u64) -> Vec<ObjectID> {
+        let Some(config) = self.config.clone() else {
+            return vec![];
+        };
+        let budget = config.per_object_budget.max(1);
+
+        // every sealed checkpoint used up one budget of each object
+        let sealed = checkpoint.saturating_sub(self.checkpoint);
+        self.checkpoint = checkpoint;
+        self.load.retain(|_, load| {
+            *load = load.saturating_sub(sealed.saturating_mul(budget));
+            *load > 0
+        });
+
+        let objects = mutable_shared_objects(tx_data);
+        let cost = match config.mode {
+            CongestionCostMode::TxCount => 1,
+            CongestionCostMode::GasBudget => tx_data.gas_budget(),
+        };
+
+        let deferrals = objects
+            .iter()
+            .map(|id| {
+                let load = self.load.get(id).copied().unwrap_or(0);
+                (*id, deferral(load, cost, budget))
+            })
+            .collect::<Vec<_>>();
+
+        let congested = deferrals
+            .iter()
+            .filter(|(_, deferral)| *deferral > config.max_deferral_checkpoints)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        if !congested.is_empty() {
+            return congested;
+        }
+
+        // the transaction runs in the latest checkpoint any of its objects defers it to
+        let deferral = deferrals.iter().map(|(_, deferral)| *deferral).max();
+
+        for id in objects {
+            let load = self.load.entry(id).or_default();
+            *load = (*load).max(deferral.unwrap_or(0) * budget) + cost;
+        }
+
+        vec![]
+    }
+}
+
+// checkpoints from the current one until the object has room for `cost`; like validators, a
+// transaction over the whole budget is let through when it's the first one in its checkpoint
+fn deferral(load: u64, cost: u64, budget: u64) -> u64 {
+    let checkpoint = load / budget;
+    let used = load % budget;
+
+    if used > 0 && used + cost > budget {
+        checkpoint + 1
+    } else {
+        checkpoint
+    }
+}
+
+fn mutable_shared_objects(tx_data: &TransactionData) -> Vec<ObjectID> {
+    tx_data
+        .kind()
+        .input_objects()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|input| match input {
+            InputObjectKind::SharedMoveObject {
+                id, mutable: true, ..
+            } => Some(id),
+            _ => None,
+        })
+        .collect()
+}
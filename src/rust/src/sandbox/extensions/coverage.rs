@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_core_types::language_storage::ModuleId;
+use move_coverage::coverage_map::{CoverageMap, ExecCoverageMap, ModuleCoverageMap};
+use move_trace_format::format::{MoveTrace, TraceEvent};
+
+use crate::sandbox::{extensions::stack_traces::line_and_column, storage::StorageExtension};
+
+// pc -> number of times the instruction was executed
+type FunctionCoverage = BTreeMap<u16, u64>;
+
+const EXEC_ID: &str = "sandbox";
+
+// modules are keyed by the package version that executed them, not by their runtime id, so
+// upgraded code is mapped against its own bytecode and sources
+#[derive(Default)]
+pub struct CoverageExtension {
+    enabled: bool,
+    modules: BTreeMap<ModuleId, BTreeMap<String, FunctionCoverage>>,
+    // number of times each function was entered
+    calls: BTreeMap<ModuleId, BTreeMap<String, u64>>,
+}
+
+impl CoverageExtension {
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.modules.clear();
+        self.calls.clear();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn record(&mut self, trace: &MoveTrace) {
+        let mut frames: Vec<(ModuleId, String)> = vec![];
+
+        for event in &trace.events {
+            match event {
+                TraceEvent::OpenFrame { frame, .. } => {
+                    let module = ModuleId::new(frame.version_id, frame.module.name().to_owned());
+
+                    *self
+                        .calls
+                        .entry(module.clone())
+                        .or_default()
+                        .entry(frame.function_name.clone())
+                        .or_default() += 1;
+
+                    frames.push((module, frame.function_name.clone()));
+                }
+                TraceEvent::CloseFrame { .. } => {
+                    frames.pop();
+                }
+                TraceEvent::Instruction { pc, .. } => {
+                    if let Some((module, function)) = frames.last() {
+                        *self
+                            .modules
+                            .entry(module.clone())
+                            .or_default()
+                            .entry(function.clone())
+                            .or_default()
+                            .entry(*pc)
+                            .or_default() += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // the format read by `sui move coverage`
+    pub fn to_coverage_map(&self) -> CoverageMap {
+        let mut exec_map = ExecCoverageMap::new(EXEC_ID.to_string());
+
+        for (module, functions) in &self.modules {
+            let module_map = exec_map
+                .module_maps
+                .entry((*module.address(), module.name().to_owned()))
+                .or_insert_with(|| {
+                    ModuleCoverageMap::new(*module.address(), module.name().to_owned())
+                });
+
+            for (function, pcs) in functions {
+                let function_map = module_map
+                    .function_maps
+                    .entry(function.as_str().into())
+                    .or_default();
+
+                for (pc, count) in pcs {
+                    *function_map.entry(*pc as u64).or_default() += count;
+                }
+            }
+        }
+
+        CoverageMap {
+            exec_maps: BTreeMap::from([(EXEC_ID.to_string(), exec_map)]),
+        }
+    }
+
+    // only modules published with source maps can be mapped to lines, others are skipped
+    pub fn to_lcov(&self, storage: &StorageExtension) -> String {
+        let mut lcov = String::new();
+
+        for (module_id, functions) in &self.modules {
+            let (Some(module), Some(source)) = (
+                storage.module_at(module_id),
+                storage.debug_info().module_source(module_id),
+            ) else {
+                continue;
+            };
+
+            let mut lines: BTreeMap<u32, u64> = BTreeMap::new();
+            let mut function_records = vec![];
+
+            for (index, definition) in module.function_defs().iter().enumerate() {
+                let Some(code) = &definition.code else {
+                    continue;
+                };
+
+                let name = module
+                    .identifier_at(module.function_handle_at(definition.function).name)
+                    .to_string();
+                let hits = functions.get(&name);
+                let index = FunctionDefinitionIndex(index as u16);
+
+                for pc in 0..code.code.len() as u16 {
+                    let Ok(location) = source.source_map.get_code_location(index, pc) else {
+                        continue;
+                    };
+
+                    let (line, _) = line_and_column(&source.contents, location.start());
+                    let count = hits.and_then(|hits| hits.get(&pc)).copied().unwrap_or(0);
+
+                    let entry = lines.entry(line).or_default();
+                    *entry = (*entry).max(count);
+                }
+
+                let declared_at = source
+                    .source_map
+                    .get_function_source_map(index)
+                    .map(|function| {
+                        line_and_column(&source.contents, function.definition_location.start()).0
+                    })
+                    .unwrap_or(0);
+                let calls = self
+                    .calls
+                    .get(module_id)
+                    .and_then(|calls| calls.get(&name))
+                    .copied()
+                    .unwrap_or(0);
+
+                function_records.push((name, declared_at, calls));
+            }
+
+            lcov.push_str(&format!("SF:{}\n", source.file_name));
+
+            for (name, line, _) in &function_records {
+                lcov.push_str(&format!("FN:{line},{name}\n"));
+            }
+            for (name, _, calls) in &function_records {
+                lcov.push_str(&format!("FNDA:{calls},{name}\n"));
+            }
+            lcov.push_str(&format!("FNF:{}\n", function_records.len()));
+            lcov.push_str(&format!(
+                "FNH:{}\n",
+                function_records
+                    .iter()
+                    .filter(|(_, _, calls)| *calls > 0)
+                    .count()
+            ));
+
+            for (line, count) in &lines {
+                lcov.push_str(&format!("DA:{line},{count}\n"));
+            }
+            lcov.push_str(&format!("LF:{}\n", lines.len()));
+            lcov.push_str(&format!(
+                "LH:{}\n",
+                lines.values().filter(|count| **count > 0).count()
+            ));
+            lcov.push_str("end_of_record\n");
+        }
+
+        lcov
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use anyhow::anyhow;
+use move_core_types::language_storage::StructTag;
+use sui_json_rpc_types::{DisplayFieldsResponse, SuiMoveStruct, SuiMoveValue};
+use sui_types::{
+    display::DisplayVersionUpdatedEvent,
+    error::SuiObjectResponseError,
+    event::Event,
+    object::{bounded_visitor::BoundedVisitor, Object},
+    TypeTag, SUI_FRAMEWORK_ADDRESS,
+};
+
+use crate::sandbox::storage::StorageExtension;
+
+pub trait DisplayExtension {
+    fn track_display_updates(&mut self, events: &[Event]);
+
+    fn render_display(&self, object: &Object) -> DisplayFieldsResponse;
+}
+
+impl DisplayExtension for StorageExtension {
+    // Like a fullnode, only `display::update_version` makes a Display visible,
+    // creating the object alone is not enough.
+    fn track_display_updates(&mut self, events: &[Event]) {
+        for event in events {
+            let Some(object_type) = display_updated_type(&event.type_) else {
+                continue;
+            };
+
+            if let Ok(update) = bcs::from_bytes::<DisplayVersionUpdatedEvent>(&event.contents) {
+                self.set_display(object_type.clone(), update);
+            }
+        }
+    }
+
+    fn render_display(&self, object: &Object) -> DisplayFieldsResponse {
+        let Some(display) = object.struct_tag().and_then(|tag| self.display_for(&tag)) else {
+            return DisplayFieldsResponse {
+                data: None,
+                error: None,
+            };
+        };
+
+        let move_struct = match decode_struct(self, object) {
+            Ok(move_struct) => move_struct,
+            Err(e) => {
+                return DisplayFieldsResponse {
+                    data: None,
+                    error: Some(SuiObjectResponseError::DisplayError {
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        };
+
+        let mut data = BTreeMap::new();
+        let mut errors = vec![];
+
+        for entry in &display.fields.contents {
+            match render_template(&entry.value, &move_struct) {
+                Ok(value) => {
+                    data.insert(entry.key.clone(), value);
+                }
+                Err(e) => errors.push(e.to_string()),
+            }
+        }
+
+        DisplayFieldsResponse {
+            data: Some(data),
+            error: (!errors.is_empty()).then(|| SuiObjectResponseError::DisplayError {
+                error: errors.join("; "),
+            }),
+        }
+    }
+}
+
+fn display_updated_type(event_type: &StructTag) -> Option<&StructTag> {
+    if event_type.address != SUI_FRAMEWORK_ADDRESS
+        || event_type.module.as_str() != "display"
+        || event_type.name.as_str() != "VersionUpdated"
+    {
+        return None;
+    }
+
+    match event_type.type_params.first() {
+        Some(TypeTag::Struct(tag)) => Some(tag),
+        _ => None,
+    }
+}
+
+fn decode_struct(storage: &StorageExtension, object: &Object) -> anyhow::Result<SuiMoveStruct> {
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or(anyhow!("Object {} is not a move-object", object.id()))?;
+
+    let layout = move_object.get_layout(storage.as_inner())?;
+    let decoded = BoundedVisitor::deserialize_struct(move_object.contents(), &layout)?;
+
+    Ok(SuiMoveStruct::from(decoded))
+}
+
+// code adjusted from the fullnode `parse_template` in sui-json-rpc read api
+fn render_template(template: &str, move_struct: &SuiMoveStruct) -> anyhow::Result<String> {
+    let mut output = template.to_string();
+    let mut var_name = String::new();
+    let mut in_braces = false;
+    let mut escaped = false;
+
+    for ch in template.chars() {
+        match ch {
+            '\\' => {
+                escaped = true;
+                continue;
+            }
+            '{' if !escaped => {
+                in_braces = true;
+                var_name.clear();
+            }
+            '}' if !escaped => {
+                in_braces = false;
+                let value = field_value(move_struct, &var_name)?;
+                output = output.replace(&format!("{{{var_name}}}"), &value.to_string());
+            }
+            _ if !escaped && in_braces => var_name.push(ch),
+            _ => {}
+        }
+        escaped = false;
+    }
+
+    Ok(output.replace("\\{", "{").replace("\\}", "}"))
+}
+
+fn field_value(move_struct: &SuiMoveStruct, path: &str) -> anyhow::Result<SuiMoveValue> {
+    let mut parts = path.split('.');
+    let first = parts.next().unwrap_or_default();
+
+    let mut current = struct_field(move_struct, first)?;
+
+    for part in parts {
+        current = match current {
+            SuiMoveValue::Struct(inner) => struct_field(&inner, part)?,
+            _ => anyhow::bail!("Unexpected path `{path}` in Display template"),
+        };
+    }
+
+    match current {
+        SuiMoveValue::Vector(_) => {
+            anyhow::bail!("Vector is not supported as a Display value (field `{path}`)")
+        }
+        SuiMoveValue::Struct(_) => {
+            anyhow::bail!("Struct is not supported as a Display value (field `{path}`)")
+        }
+        value => Ok(value),
+    }
+}
+
+fn struct_field(move_struct: &SuiMoveStruct, name: &str) -> anyhow::Result<SuiMoveValue> {
+    let fields = match move_struct {
+        SuiMoveStruct::WithTypes { fields, .. } | SuiMoveStruct::WithFields(fields) => fields,
+        SuiMoveStruct::Runtime(_) => anyhow::bail!("Struct without field names in Display"),
+    };
+
+    fields
+        .get(name)
+        .cloned()
+        .ok_or(anyhow!("Field `{name}` not found in Display template"))
+}
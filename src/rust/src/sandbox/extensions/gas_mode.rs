@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+use sui_types::{
+    gas::SuiGasStatus, object::Object, supported_protocol_versions::ProtocolConfig,
+    transaction::GasData,
+};
+
+use crate::sandbox::storage::StorageExtension;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GasMode {
+    // no gas coin is needed and nothing is charged
+    Unmetered,
+    #[default]
+    Metered,
+    // metered, the gas owner is funded with whatever the payment is missing to cover the budget
+    #[serde(rename = "metered-but-sponsored-by-faucet")]
+    FaucetSponsored,
+}
+
+impl FromStr for GasMode {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> anyhow::Result<Self> {
+        [
+            GasMode::Unmetered,
+            GasMode::Metered,
+            GasMode::FaucetSponsored,
+        ]
+        .into_iter()
+        .find(|candidate| candidate.as_str() == mode)
+        .ok_or(anyhow::anyhow!(
+            "expected `unmetered`, `metered` or `metered-but-sponsored-by-faucet`, got `{mode}`"
+        ))
+    }
+}
+
+impl GasMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GasMode::Unmetered => "unmetered",
+            GasMode::Metered => "metered",
+            GasMode::FaucetSponsored => "metered-but-sponsored-by-faucet",
+        }
+    }
+
+    // the other modes don't need the sender to hold any gas coins
+    pub fn needs_payment(&self) -> bool {
+        *self == GasMode::Metered
+    }
+
+    pub fn gas_status(
+        &self,
+        gas_data: &GasData,
+        reference_price: u64,
+        config: &ProtocolConfig,
+    ) -> anyhow::Result<SuiGasStatus> {
+        match self {
+            GasMode::Unmetered => Ok(SuiGasStatus::new_unmetered()),
+            GasMode::Metered | GasMode::FaucetSponsored => Ok(SuiGasStatus::new(
+                gas_data.budget,
+                gas_data.price,
+                reference_price,
+                config,
+            )?),
+        }
+    }
+
+    // the coin the faucet adds to the payment to cover the budget, it's only stored once the
+    // transaction passed validation
+    pub fn faucet_coin(&self, gas_data: &GasData, storage: &StorageExtension) -> Option<Object> {
+        let GasMode::FaucetSponsored = self else {
+            return None;
+        };
+
+        let balance: u64 = gas_data
+            .payment
+            .iter()
+            .filter_map(|(id, _, _)| storage.get_object(id))
+            .filter(|object| object.is_gas_coin())
+            .map(|object| object.get_coin_value_unsafe())
+            .sum();
+
+        (balance < gas_data.budget).then(|| {
+            Object::new_gas_with_balance_and_owner_for_testing(
+                gas_data.budget - balance,
+                gas_data.owner,
+            )
+        })
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use sui_types::{
+    error::UserInputError,
+    supported_protocol_versions::ProtocolConfig,
+    transaction::{GasData, TransactionData, TransactionDataAPI},
+};
+
+use crate::sandbox::{extensions::gas_mode::GasMode, storage::StorageExtension};
+
+// the checks a validator runs on the gas payment before the coins are smashed into the first one
+pub fn check_gas_payment(
+    tx_data: &TransactionData,
+    gas_data: &GasData,
+    gas_mode: GasMode,
+    storage: &StorageExtension,
+    config: &ProtocolConfig,
+) -> Result<(), UserInputError> {
+    if gas_data.payment.is_empty() {
+        return match gas_mode {
+            GasMode::Unmetered | GasMode::FaucetSponsored => Ok(()),
+            _ => Err(UserInputError::MissingGasPayment),
+        };
+    }
+
+    if gas_data.payment.len() > config.max_gas_payment_objects() as usize {
+        return Err(UserInputError::SizeLimitExceeded {
+            limit: "maximum number of gas payment objects".to_string(),
+            value: config.max_gas_payment_objects().to_string(),
+        });
+    }
+
+    let mut used = HashSet::new();
+
+    for (object_id, _, _) in &gas_data.payment {
+        if !used.insert(*object_id) {
+            return Err(UserInputError::DuplicateObjectRefInput);
+        }
+
+        match storage.get_object(object_id) {
+            Some(object) if !object.is_gas_coin() => {
+                return Err(UserInputError::InvalidGasObject {
+                    object_id: *object_id,
+                })
+            }
+            Some(_) => {}
+            None => {
+                return Err(UserInputError::ObjectNotFound {
+                    object_id: *object_id,
+                    version: None,
+                })
+            }
+        }
+    }
+
+    // the gas coin can only be used through the `GasCoin` argument
+    let inputs = tx_data.kind().input_objects()?;
+
+    if inputs.iter().any(|input| used.contains(&input.object_id())) {
+        return Err(UserInputError::DuplicateObjectRefInput);
+    }
+
+    Ok(())
+}
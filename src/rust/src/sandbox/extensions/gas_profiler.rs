@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_trace_format::format::{MoveTrace, TraceEvent};
+use sui_types::{
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI},
+    inner_temporary_store::InnerTemporaryStore,
+    transaction::{Command, TransactionData, TransactionDataAPI, TransactionKind},
+};
+
+use crate::{
+    sandbox::storage::StorageExtension,
+    types::{CommandGas, FunctionGas, GasProfile, ObjectStorageGas},
+};
+
+#[derive(Default)]
+pub struct GasProfilerExtension {
+    enabled: bool,
+    profiles: HashMap<TransactionDigest, GasProfile>,
+}
+
+struct OpenFrame {
+    function: String,
+    gas_at_open: u64,
+    children: u64,
+}
+
+impl GasProfilerExtension {
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn profile(&self, digest: &TransactionDigest) -> Option<&GasProfile> {
+        self.profiles.get(digest)
+    }
+
+    pub fn record(
+        &mut self,
+        tx_data: &TransactionData,
+        trace: &MoveTrace,
+        temporary_store: &InnerTemporaryStore,
+        effects: &TransactionEffects,
+        storage: &StorageExtension,
+    ) {
+        let commands = match tx_data.kind() {
+            TransactionKind::ProgrammableTransaction(pt) => pt.commands.clone(),
+            _ => vec![],
+        };
+
+        // frames are addressed by the original package id, whichever version the command called
+        let call_packages: Vec<Option<AccountAddress>> = commands
+            .iter()
+            .map(|command| match command {
+                Command::MoveCall(call) => Some(storage.original_package_id(&call.package).into()),
+                _ => None,
+            })
+            .collect();
+
+        let published: BTreeSet<AccountAddress> = effects
+            .created()
+            .into_iter()
+            .filter(|((id, _, _), _)| {
+                temporary_store
+                    .written
+                    .get(id)
+                    .is_some_and(|object| object.is_package())
+            })
+            .map(|((id, _, _), _)| id.into())
+            .collect();
+
+        let mut command_gas: Vec<CommandGas> = commands
+            .iter()
+            .enumerate()
+            .map(|(index, command)| CommandGas {
+                index: index as u16,
+                kind: command_kind(command).to_string(),
+                function: match command {
+                    Command::MoveCall(call) => Some(format!(
+                        "{}::{}::{}",
+                        AccountAddress::from(call.package).to_hex_literal(),
+                        call.module,
+                        call.function
+                    )),
+                    _ => None,
+                },
+                computation_units: None,
+            })
+            .collect();
+
+        let mut functions: BTreeMap<String, FunctionGas> = BTreeMap::new();
+        let mut folded: BTreeMap<String, u64> = BTreeMap::new();
+        let mut stack: Vec<OpenFrame> = vec![];
+        let mut next_command = 0;
+        let mut current_command = None;
+        let mut gas_left = 0;
+
+        let mut close_frame =
+            |stack: &mut Vec<OpenFrame>, gas_left: u64, command: Option<usize>| -> Option<u64> {
+                let frame = stack.pop()?;
+                let inclusive = frame.gas_at_open.saturating_sub(gas_left);
+                let exclusive = inclusive.saturating_sub(frame.children);
+
+                let path = stack
+                    .iter()
+                    .map(|open| open.function.as_str())
+                    .chain([frame.function.as_str()])
+                    .collect::<Vec<_>>()
+                    .join(";");
+                let prefix = command.map_or("tx".to_string(), |index| format!("command#{index}"));
+                *folded.entry(format!("{prefix};{path}")).or_default() += exclusive;
+
+                let entry =
+                    functions
+                        .entry(frame.function.clone())
+                        .or_insert_with(|| FunctionGas {
+                            function: frame.function.clone(),
+                            calls: 0,
+                            inclusive: 0,
+                            exclusive: 0,
+                        });
+                entry.calls += 1;
+                entry.exclusive += exclusive;
+
+                // recursive calls are already counted by the outermost frame
+                if stack.iter().all(|open| open.function != frame.function) {
+                    entry.inclusive += inclusive;
+                }
+
+                if let Some(parent) = stack.last_mut() {
+                    parent.children += inclusive;
+                }
+
+                Some(inclusive)
+            };
+
+        for event in &trace.events {
+            match event {
+                TraceEvent::OpenFrame {
+                    frame,
+                    gas_left: left,
+                } => {
+                    gas_left = *left;
+
+                    if stack.is_empty() {
+                        current_command = find_command(
+                            &commands,
+                            &call_packages,
+                            &published,
+                            &mut next_command,
+                            &frame.module,
+                            &frame.function_name,
+                        );
+                    }
+
+                    let function = format!(
+                        "{}::{}::{}",
+                        frame.module.address().to_hex_literal(),
+                        frame.module.name(),
+                        frame.function_name
+                    );
+
+                    stack.push(OpenFrame {
+                        function,
+                        gas_at_open: *left,
+                        children: 0,
+                    });
+                }
+                TraceEvent::CloseFrame { gas_left: left, .. } => {
+                    gas_left = *left;
+                    let depth = stack.len();
+
+                    if let Some(inclusive) = close_frame(&mut stack, gas_left, current_command) {
+                        if depth == 1 {
+                            add_command_gas(&mut command_gas, current_command, inclusive);
+                        }
+                    }
+                }
+                TraceEvent::Instruction { gas_left: left, .. } => gas_left = *left,
+                _ => {}
+            }
+        }
+
+        // frames still open when execution aborted
+        while !stack.is_empty() {
+            let depth = stack.len();
+
+            if let Some(inclusive) = close_frame(&mut stack, gas_left, current_command) {
+                if depth == 1 {
+                    add_command_gas(&mut command_gas, current_command, inclusive);
+                }
+            }
+        }
+
+        let mut functions = functions.into_values().collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.inclusive.cmp(&a.inclusive));
+
+        self.profiles.insert(
+            *effects.transaction_digest(),
+            GasProfile {
+                gas_used: effects.gas_cost_summary().clone(),
+                functions,
+                commands: command_gas,
+                storage: storage_breakdown(temporary_store),
+                folded_stacks: folded
+                    .into_iter()
+                    .filter(|(_, gas)| *gas > 0)
+                    .map(|(path, gas)| format!("{path} {gas}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            },
+        );
+    }
+}
+
+// top level frames are opened by `MoveCall` commands, or by `init` functions of the packages
+// a `Publish` created
+fn find_command(
+    commands: &[Command],
+    call_packages: &[Option<AccountAddress>],
+    published: &BTreeSet<AccountAddress>,
+    next_command: &mut usize,
+    module: &ModuleId,
+    function: &str,
+) -> Option<usize> {
+    let index = (*next_command..commands.len()).find(|index| match &commands[*index] {
+        Command::MoveCall(call) => {
+            call_packages[*index] == Some(*module.address())
+                && call.module == module.name().as_str()
+                && call.function == function
+        }
+        Command::Publish(..) => function == "init" && published.contains(module.address()),
+        _ => false,
+    })?;
+
+    *next_command = index + 1;
+
+    Some(index)
+}
+
+fn add_command_gas(commands: &mut [CommandGas], command: Option<usize>, inclusive: u64) {
+    if let Some(command) = command.and_then(|index| commands.get_mut(index)) {
+        *command.computation_units.get_or_insert(0) += inclusive;
+    }
+}
+
+fn command_kind(command: &Command) -> &'static str {
+    match command {
+        Command::MoveCall(_) => "MoveCall",
+        Command::TransferObjects(..) => "TransferObjects",
+        Command::SplitCoins(..) => "SplitCoins",
+        Command::MergeCoins(..) => "MergeCoins",
+        Command::Publish(..) => "Publish",
+        Command::MakeMoveVec(..) => "MakeMoveVec",
+        Command::Upgrade(..) => "Upgrade",
+    }
+}
+
+// storage cost is what a written object's rebate was set to, the rebate is what the input
+// version of the object had stored
+fn storage_breakdown(temporary_store: &InnerTemporaryStore) -> Vec<ObjectStorageGas> {
+    let ids = temporary_store
+        .written
+        .keys()
+        .chain(temporary_store.input_objects.keys())
+        .collect::<BTreeSet<_>>();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let written = temporary_store.written.get(id);
+            let input = temporary_store.input_objects.get(id);
+            let object = written.or(input)?;
+
+            if object.is_package() && written.is_none() {
+                return None;
+            }
+
+            Some(ObjectStorageGas {
+                object_id: *id,
+                object_type: object.type_().map(|t| t.to_string()),
+                bytes_written: written.map_or(0, |o| o.object_size_for_gas_metering() as u64),
+                storage_cost: written.map_or(0, |o| o.storage_rebate),
+                storage_rebate: input.map_or(0, |o| o.storage_rebate),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use sui_types::{
+    base_types::{ObjectRef, SequenceNumber},
+    committee::EpochId,
+    digests::ObjectDigest,
+    error::UserInputError,
+    gas::SuiGasStatus,
+    object::Object,
+    supported_protocol_versions::ProtocolConfig,
+    transaction::{
+        CallArg, GasData, InputObjectKind, ObjectReadResult, ObjectReadResultKind, Transaction,
+        TransactionData, TransactionDataAPI, TransactionExpiration, TransactionKind,
+    },
+};
+
+use crate::sandbox::storage::StorageExtension;
+
+// the checks a validator runs on the transaction itself, before any object is loaded
+pub fn check_transaction(
+    transaction: &Transaction,
+    tx_data: &TransactionData,
+    epoch: EpochId,
+    config: &ProtocolConfig,
+) -> Result<(), UserInputError> {
+    if let TransactionExpiration::Epoch(expiration) = tx_data.expiration() {
+        if *expiration < epoch {
+            return Err(UserInputError::TransactionExpired);
+        }
+    }
+
+    let size = bcs::serialized_size(transaction.data()).unwrap_or(usize::MAX);
+
+    if size > config.max_tx_size_bytes() as usize {
+        return Err(size_limit_exceeded(
+            "serialized transaction size",
+            config.max_tx_size_bytes(),
+        ));
+    }
+
+    let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+        return Ok(());
+    };
+
+    if pt.inputs.len() > config.max_input_objects() as usize {
+        return Err(size_limit_exceeded(
+            "maximum input objects in a transaction",
+            config.max_input_objects(),
+        ));
+    }
+
+    if pt.commands.len() > config.max_programmable_tx_commands() as usize {
+        return Err(size_limit_exceeded(
+            "maximum commands in a programmable transaction",
+            config.max_programmable_tx_commands(),
+        ));
+    }
+
+    for input in &pt.inputs {
+        if let CallArg::Pure(bytes) = input {
+            if bytes.len() > config.max_pure_argument_size() as usize {
+                return Err(size_limit_exceeded(
+                    "maximum pure argument size",
+                    config.max_pure_argument_size(),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// every object referenced by the transaction, gas included, has to exist at exactly the version
+// and digest it is referenced with, and can be referenced only once
+pub fn check_input_objects(
+    tx_data: &TransactionData,
+    storage: &StorageExtension,
+) -> Result<(), UserInputError> {
+    let inputs = tx_data.kind().input_objects()?;
+    let mut used = HashSet::new();
+
+    for input in &inputs {
+        if !used.insert(input.object_id()) {
+            return Err(UserInputError::DuplicateObjectRefInput);
+        }
+
+        let object_id = input.object_id();
+        let object = storage
+            .get_object(&object_id)
+            .ok_or(UserInputError::ObjectNotFound {
+                object_id,
+                version: input.version(),
+            })?;
+
+        match input {
+            InputObjectKind::ImmOrOwnedMoveObject(object_ref) => {
+                check_object_ref(object_ref, object.version(), object.digest())?
+            }
+            InputObjectKind::SharedMoveObject {
+                initial_shared_version,
+                ..
+            } => match object.owner.start_version() {
+                Some(version) if version == *initial_shared_version => {}
+                Some(version) => {
+                    return Err(UserInputError::SharedObjectStartingVersionMismatch {
+                        object_id,
+                        expected: version,
+                        actual: *initial_shared_version,
+                    })
+                }
+                None => return Err(UserInputError::NotSharedObjectError),
+            },
+            InputObjectKind::MovePackage(_) => {}
+        }
+    }
+
+    for gas_ref in &tx_data.gas_data().payment {
+        if !used.insert(gas_ref.0) {
+            return Err(UserInputError::DuplicateObjectRefInput);
+        }
+
+        if let Some(object) = storage.get_object(&gas_ref.0) {
+            check_object_ref(gas_ref, object.version(), object.digest())?;
+        }
+    }
+
+    Ok(())
+}
+
+// budget within the protocol bounds and covered by the gas coins, including the faucet coin
+// that isn't stored yet
+pub fn check_gas_balance(
+    gas_status: &SuiGasStatus,
+    gas_data: &GasData,
+    faucet_coin: Option<&Object>,
+    storage: &StorageExtension,
+) -> Result<(), UserInputError> {
+    let gas_objects = gas_data
+        .payment
+        .iter()
+        .filter_map(|gas_ref| {
+            storage
+                .get_object(&gas_ref.0)
+                .or(faucet_coin.filter(|coin| coin.id() == gas_ref.0))
+                .map(|object| ObjectReadResult {
+                    input_object_kind: InputObjectKind::ImmOrOwnedMoveObject(*gas_ref),
+                    object: ObjectReadResultKind::Object(object.clone()),
+                })
+        })
+        .collect::<Vec<_>>();
+
+    gas_status.check_gas_balance(&gas_objects.iter().collect::<Vec<_>>(), gas_data.budget)
+}
+
+fn check_object_ref(
+    object_ref: &ObjectRef,
+    current_version: SequenceNumber,
+    current_digest: ObjectDigest,
+) -> Result<(), UserInputError> {
+    if object_ref.1 != current_version {
+        return Err(UserInputError::ObjectVersionUnavailableForConsumption {
+            provided_obj_ref: *object_ref,
+            current_version,
+        });
+    }
+
+    if object_ref.2 != current_digest {
+        return Err(UserInputError::InvalidObjectDigest {
+            object_id: object_ref.0,
+            expected_digest: current_digest,
+        });
+    }
+
+    Ok(())
+}
+
+fn size_limit_exceeded(limit: &str, value: impl ToString) -> UserInputError {
+    UserInputError::SizeLimitExceeded {
+        limit: limit.to_string(),
+        value: value.to_string(),
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+};
+
+use serde::Deserialize;
+use sui_types::{
+    base_types::ObjectID,
+    crypto::Signature,
+    digests::TransactionDigest,
+    transaction::{InputObjectKind, TransactionData, TransactionDataAPI},
+};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum OrderingStrategy {
+    // submission order
+    #[default]
+    Fifo,
+    // a shuffle that is the same for every run with the same seed
+    Random {
+        seed: u64,
+    },
+    // an adversary that sees the pending transactions front-runs the ones its transaction
+    // conflicts with: transactions contending for a shared object are ordered by gas price,
+    // highest first, and then by submission, latest first; the others keep their position
+    Adversarial,
+}
+
+pub struct PendingTransaction {
+    pub tx_data: TransactionData,
+    pub signatures: Vec<Signature>,
+}
+
+// transactions submitted but not executed yet, their owned inputs are locked on submission
+#[derive(Default)]
+pub struct MempoolExtension {
+    pending: Vec<PendingTransaction>,
+}
+
+impl MempoolExtension {
+    pub fn submit(&mut self, tx_data: TransactionData, signatures: Vec<Signature>) {
+        self.pending.push(PendingTransaction {
+            tx_data,
+            signatures,
+        });
+    }
+
+    pub fn pending(&self) -> Vec<TransactionDigest> {
+        self.pending.iter().map(|tx| tx.tx_data.digest()).collect()
+    }
+
+    // owned-only transactions don't go through consensus and keep their submission order,
+    // followed by the ones using shared objects in the order consensus picked
+    pub fn seal(&mut self, strategy: OrderingStrategy) -> Vec<PendingTransaction> {
+        let (mut batch, mut shared): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|tx| !uses_shared_objects(&tx.tx_data));
+
+        match strategy {
+            OrderingStrategy::Fifo => {}
+            OrderingStrategy::Random { seed } => shuffle(&mut shared, seed),
+            OrderingStrategy::Adversarial => front_run(&mut shared),
+        }
+
+        batch.append(&mut shared);
+        batch
+    }
+}
+
+fn uses_shared_objects(tx_data: &TransactionData) -> bool {
+    tx_data
+        .kind()
+        .input_objects()
+        .unwrap_or_default()
+        .iter()
+        .any(|input| matches!(input, InputObjectKind::SharedMoveObject { .. }))
+}
+
+// transactions conflict when they use the same shared object and one of them mutably, every
+// group of conflicting transactions is reordered within the positions it takes up
+fn front_run(transactions: &mut Vec<PendingTransaction>) {
+    let objects = transactions
+        .iter()
+        .map(|tx| shared_objects(&tx.tx_data))
+        .collect::<Vec<_>>();
+    let mutated = objects
+        .iter()
+        .flatten()
+        .filter(|(_, mutable)| *mutable)
+        .map(|(id, _)| *id)
+        .collect::<HashSet<_>>();
+
+    // union-find over the transactions, joined by the contended objects they share
+    let mut groups = (0..transactions.len()).collect::<Vec<_>>();
+    let mut owners: HashMap<ObjectID, usize> = HashMap::new();
+
+    for (index, used) in objects.iter().enumerate() {
+        for (id, _) in used.iter().filter(|(id, _)| mutated.contains(id)) {
+            match owners.get(id) {
+                Some(owner) => {
+                    let (a, b) = (find(&mut groups, *owner), find(&mut groups, index));
+                    groups[a.max(b)] = a.min(b);
+                }
+                None => {
+                    owners.insert(*id, index);
+                }
+            }
+        }
+    }
+
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for index in 0..transactions.len() {
+        let group = find(&mut groups, index);
+        members.entry(group).or_default().push(index);
+    }
+
+    let mut order = (0..transactions.len()).collect::<Vec<_>>();
+    for slots in members.values() {
+        let mut ranked = slots.clone();
+        ranked.sort_by_key(|index| {
+            (
+                Reverse(transactions[*index].tx_data.gas_price()),
+                Reverse(*index),
+            )
+        });
+
+        for (slot, index) in slots.iter().zip(ranked) {
+            order[*slot] = index;
+        }
+    }
+
+    let mut pending = transactions.drain(..).map(Some).collect::<Vec<_>>();
+    transactions.extend(order.into_iter().filter_map(|index| pending[index].take()));
+}
+
+fn find(groups: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while groups[root] != root {
+        root = groups[root];
+    }
+    groups[index] = root;
+
+    root
+}
+
+fn shared_objects(tx_data: &TransactionData) -> Vec<(ObjectID, bool)> {
+    tx_data
+        .kind()
+        .input_objects()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|input| match input {
+            InputObjectKind::SharedMoveObject { id, mutable, .. } => Some((id, mutable)),
+            _ => None,
+        })
+        .collect()
+}
+
+// fisher-yates with splitmix64, so the order only depends on the seed
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    };
+
+    for i in (1..items.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
@@ -1,5 +1,16 @@
+pub mod aborts;
 pub mod auth_extension;
 pub mod changes;
 pub mod coins;
+pub mod congestion;
+pub mod coverage;
+pub mod display;
+pub mod gas_mode;
+pub mod gas_payment;
+pub mod gas_profiler;
+pub mod input_validation;
+pub mod mempool;
+pub mod object_locks;
+pub mod stack_traces;
 pub mod time_extension;
 pub mod transaction_control;
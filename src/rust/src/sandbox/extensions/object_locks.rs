@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+
+use sui_types::{
+    base_types::ObjectRef,
+    digests::TransactionDigest,
+    error::SuiError,
+    object::Owner,
+    transaction::{InputObjectKind, TransactionData, TransactionDataAPI},
+};
+
+use crate::sandbox::storage::StorageExtension;
+
+// owned object versions locked by the transaction a validator signed them for, a lock is held
+// until the transaction is executed and the version consumed
+#[derive(Default)]
+pub struct ObjectLockExtension {
+    locks: HashMap<ObjectRef, TransactionDigest>,
+}
+
+impl ObjectLockExtension {
+    // locks either all owned inputs of the transaction or none of them
+    pub fn acquire(
+        &mut self,
+        tx_data: &TransactionData,
+        storage: &StorageExtension,
+    ) -> Result<(), SuiError> {
+        let digest = tx_data.digest();
+        let refs = owned_object_refs(tx_data, storage);
+
+        // versions consumed or replaced since they were locked can't be used anymore
+        self.locks.retain(|obj_ref, _| {
+            storage
+                .get_object(&obj_ref.0)
+                .is_some_and(|object| object.version() == obj_ref.1)
+        });
+
+        for obj_ref in &refs {
+            if let Some(pending_transaction) = self.locks.get(obj_ref) {
+                if *pending_transaction != digest {
+                    return Err(SuiError::ObjectLockConflict {
+                        obj_ref: *obj_ref,
+                        pending_transaction: *pending_transaction,
+                    });
+                }
+            }
+        }
+
+        for obj_ref in refs {
+            self.locks.insert(obj_ref, digest);
+        }
+
+        Ok(())
+    }
+
+    pub fn release(&mut self, digest: &TransactionDigest) {
+        self.locks.retain(|_, locked_by| locked_by != digest);
+    }
+
+    pub fn held_by(&self, digest: &TransactionDigest) -> HashSet<ObjectRef> {
+        self.locks
+            .iter()
+            .filter(|(_, locked_by)| *locked_by == digest)
+            .map(|(obj_ref, _)| *obj_ref)
+            .collect()
+    }
+
+    // releases the locks of the transaction except the versions it already held
+    pub fn release_except(&mut self, digest: &TransactionDigest, held: &HashSet<ObjectRef>) {
+        self.locks
+            .retain(|obj_ref, locked_by| locked_by != digest || held.contains(obj_ref));
+    }
+
+    pub fn clear(&mut self) {
+        self.locks.clear();
+    }
+}
+
+fn owned_object_refs(tx_data: &TransactionData, storage: &StorageExtension) -> Vec<ObjectRef> {
+    let inputs = tx_data
+        .kind()
+        .input_objects()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|input| match input {
+            InputObjectKind::ImmOrOwnedMoveObject(obj_ref) => Some(obj_ref),
+            _ => None,
+        })
+        .filter(|obj_ref| {
+            storage
+                .get_object(&obj_ref.0)
+                .is_some_and(|object| matches!(object.owner, Owner::AddressOwner(_)))
+        });
+
+    tx_data
+        .gas_data()
+        .payment
+        .iter()
+        .copied()
+        .chain(inputs)
+        .collect()
+}
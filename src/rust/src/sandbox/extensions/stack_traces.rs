@@ -0,0 +1,144 @@
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_bytecode_source_map::source_map::SourceMap;
+use move_command_line_common::files::FileHash;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use move_trace_format::format::{MoveTrace, TraceEvent};
+use sui_types::{base_types::ObjectID, digests::TransactionDigest};
+
+use crate::{
+    sandbox::storage::{
+        debug_info::{CallFrame, ModuleSource},
+        StorageExtension,
+    },
+    types::{SourceFile, StackFrame},
+};
+
+pub trait StackTraceExtension {
+    fn record_abort_stack(&mut self, digest: TransactionDigest, trace: &MoveTrace);
+
+    // keeps the source map of every module whose source file is among `sources`
+    fn add_package_sources(
+        &mut self,
+        package_id: ObjectID,
+        source_maps: Vec<(Identifier, SourceMap)>,
+        sources: &[SourceFile],
+    );
+
+    fn stack_trace(&self, digest: &TransactionDigest) -> Vec<StackFrame>;
+}
+
+impl StackTraceExtension for StorageExtension {
+    fn record_abort_stack(&mut self, digest: TransactionDigest, trace: &MoveTrace) {
+        let stack = call_stack(trace);
+
+        if !stack.is_empty() {
+            self.debug_info_mut().record_abort_stack(digest, stack);
+        }
+    }
+
+    fn add_package_sources(
+        &mut self,
+        package_id: ObjectID,
+        source_maps: Vec<(Identifier, SourceMap)>,
+        sources: &[SourceFile],
+    ) {
+        for (module, source_map) in source_maps {
+            let file_hash = source_map.definition_location.file_hash();
+
+            let Some(source) = sources
+                .iter()
+                .find(|source| FileHash::new(&source.contents) == file_hash)
+            else {
+                continue;
+            };
+
+            self.debug_info_mut().add_module_source(
+                ModuleId::new(package_id.into(), module),
+                ModuleSource {
+                    file_name: source.path.clone(),
+                    contents: source.contents.clone(),
+                    source_map,
+                },
+            );
+        }
+    }
+
+    fn stack_trace(&self, digest: &TransactionDigest) -> Vec<StackFrame> {
+        let Some(stack) = self.debug_info().abort_stack(digest) else {
+            return vec![];
+        };
+
+        stack
+            .iter()
+            .rev()
+            .map(|frame| {
+                let mut stack_frame = StackFrame {
+                    package: ObjectID::from(*frame.module.address()),
+                    module: frame.module.name().to_string(),
+                    function: frame.function.clone(),
+                    pc: frame.pc,
+                    file: None,
+                    line: None,
+                    column: None,
+                };
+
+                if let Some(source) = self.module_source(&frame.module) {
+                    let location = frame.pc.and_then(|pc| {
+                        source
+                            .source_map
+                            .get_code_location(FunctionDefinitionIndex(frame.function_index), pc)
+                            .ok()
+                    });
+
+                    if let Some(location) = location {
+                        let (line, column) = line_and_column(&source.contents, location.start());
+
+                        stack_frame.file = Some(source.file_name.clone());
+                        stack_frame.line = Some(line);
+                        stack_frame.column = Some(column);
+                    }
+                }
+
+                stack_frame
+            })
+            .collect()
+    }
+}
+
+// frames opened but never closed are the ones that were running when execution aborted
+fn call_stack(trace: &MoveTrace) -> Vec<CallFrame> {
+    let mut stack: Vec<CallFrame> = vec![];
+
+    for event in &trace.events {
+        match event {
+            TraceEvent::OpenFrame { frame, .. } => stack.push(CallFrame {
+                module: frame.module.clone(),
+                function: frame.function_name.clone(),
+                function_index: frame.binary_member_index,
+                pc: None,
+            }),
+            TraceEvent::CloseFrame { .. } => {
+                stack.pop();
+            }
+            TraceEvent::Instruction { pc, .. } => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.pc = Some(*pc);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    stack
+}
+
+pub fn line_and_column(contents: &str, offset: u32) -> (u32, u32) {
+    let prefix = contents.get(..offset as usize).unwrap_or(contents);
+    let line = prefix.matches('\n').count() as u32 + 1;
+    let column = prefix
+        .rfind('\n')
+        .map_or(prefix.len(), |newline| prefix.len() - newline - 1) as u32
+        + 1;
+
+    (line, column)
+}
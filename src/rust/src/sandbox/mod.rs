@@ -22,15 +22,26 @@ mod storage;
 mod transaction_pipeline;
 
 pub use crate::sandbox::extensions::{
+    aborts::AbortExtension,
     auth_extension::{AuthExtension, AuthMode},
     coins::CoinExtension,
+    congestion::{CongestionConfig, CongestionControlExtension},
+    coverage::CoverageExtension,
+    display::DisplayExtension,
+    gas_mode::GasMode,
+    gas_profiler::GasProfilerExtension,
+    mempool::{MempoolExtension, OrderingStrategy},
+    object_locks::ObjectLockExtension,
+    stack_traces::StackTraceExtension,
     transaction_control::TransactionControlExtension,
 };
 pub use builder::SandboxBuilder;
+pub use engines::verification::PublishVerificationError;
 pub use storage::StorageExtension;
 
 use crate::sandbox::engines::{
-    clock::ClockEngine, package::PackageEngine, transaction::TransactionEngine,
+    clock::ClockEngine, object::ObjectEngine, package::PackageEngine,
+    transaction::TransactionEngine,
 };
 
 pub struct MoveVMSandbox {
@@ -41,6 +52,14 @@ pub struct MoveVMSandbox {
     auth_extension: AuthExtension,
     reference_price: u64,
     transaction_control: TransactionControlExtension,
+    coverage: CoverageExtension,
+    gas_profiler: GasProfilerExtension,
+    // records the Move call stack of aborted transactions
+    abort_stack_traces: bool,
+    gas_mode: GasMode,
+    object_locks: ObjectLockExtension,
+    congestion: CongestionControlExtension,
+    mempool: MempoolExtension,
 }
 
 impl MoveVMSandbox {
@@ -104,6 +123,14 @@ impl MoveVMSandbox {
         PackageEngine::new(self)
     }
 
+    pub fn object(&self) -> ObjectEngine<&MoveVMSandbox> {
+        ObjectEngine::new(self)
+    }
+
+    pub fn object_mut(&mut self) -> ObjectEngine<&mut MoveVMSandbox> {
+        ObjectEngine::new(self)
+    }
+
     pub fn storage(&self) -> &StorageExtension {
         &self.storage
     }
@@ -112,6 +139,14 @@ impl MoveVMSandbox {
         &mut self.storage
     }
 
+    // locks are taken against the versions of the state being replaced
+    pub fn restore_from_snapshot(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        self.storage.restone_from_bytes(bytes)?;
+        self.object_locks.clear();
+
+        Ok(())
+    }
+
     pub fn get_object(&self, id: ObjectID) -> ObjectRead {
         let object = self.storage.get_object(&id);
 
@@ -137,6 +172,46 @@ impl MoveVMSandbox {
         self.transaction_control.reject_with(reason);
     }
 
+    pub fn coverage(&self) -> &CoverageExtension {
+        &self.coverage
+    }
+
+    pub fn coverage_mut(&mut self) -> &mut CoverageExtension {
+        &mut self.coverage
+    }
+
+    pub fn gas_profiler(&self) -> &GasProfilerExtension {
+        &self.gas_profiler
+    }
+
+    pub fn gas_profiler_mut(&mut self) -> &mut GasProfilerExtension {
+        &mut self.gas_profiler
+    }
+
+    pub fn enable_abort_stack_traces(&mut self) {
+        self.abort_stack_traces = true;
+    }
+
+    pub fn disable_abort_stack_traces(&mut self) {
+        self.abort_stack_traces = false;
+    }
+
+    pub fn gas_mode(&self) -> GasMode {
+        self.gas_mode
+    }
+
+    pub fn set_gas_mode(&mut self, mode: GasMode) {
+        self.gas_mode = mode;
+    }
+
+    pub fn mempool(&self) -> &MempoolExtension {
+        &self.mempool
+    }
+
+    pub fn set_congestion_control(&mut self, config: Option<CongestionConfig>) {
+        self.congestion.set_config(config);
+    }
+
     pub fn disable_signature_checks(&mut self) {
         self.auth_extension.set_mode(AuthMode::Disabled);
     }
@@ -162,4 +237,8 @@ impl MoveVMSandbox {
     pub fn gas_price(&self) -> u64 {
         self.reference_price
     }
+
+    pub fn protocol_config(&self) -> &ProtocolConfig {
+        &self.config
+    }
 }
@@ -0,0 +1,61 @@
+use std::collections::{HashMap, VecDeque};
+
+use move_bytecode_source_map::source_map::SourceMap;
+use move_core_types::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+use sui_types::digests::TransactionDigest;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleSource {
+    pub file_name: String,
+    pub contents: String,
+    pub source_map: SourceMap,
+}
+
+// a Move frame that was still open when the transaction aborted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallFrame {
+    pub module: ModuleId,
+    pub function: String,
+    pub function_index: u16,
+    pub pc: Option<u16>,
+}
+
+// the oldest abort stacks are dropped past this, they are kept in snapshots taken with
+// debug info
+const MAX_ABORT_STACKS: usize = 1000;
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct DebugInfo {
+    // keyed by the module id of the package version that was published with the sources
+    module_sources: HashMap<ModuleId, ModuleSource>,
+    abort_stacks: HashMap<TransactionDigest, Vec<CallFrame>>,
+    // insertion order of `abort_stacks`
+    abort_order: VecDeque<TransactionDigest>,
+}
+
+impl DebugInfo {
+    pub fn add_module_source(&mut self, module: ModuleId, source: ModuleSource) {
+        self.module_sources.insert(module, source);
+    }
+
+    pub fn module_source(&self, module: &ModuleId) -> Option<&ModuleSource> {
+        self.module_sources.get(module)
+    }
+
+    pub fn record_abort_stack(&mut self, digest: TransactionDigest, stack: Vec<CallFrame>) {
+        if self.abort_stacks.insert(digest, stack).is_none() {
+            self.abort_order.push_back(digest);
+        }
+
+        while self.abort_order.len() > MAX_ABORT_STACKS {
+            if let Some(oldest) = self.abort_order.pop_front() {
+                self.abort_stacks.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn abort_stack(&self, digest: &TransactionDigest) -> Option<&Vec<CallFrame>> {
+        self.abort_stacks.get(digest)
+    }
+}
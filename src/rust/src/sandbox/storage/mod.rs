@@ -1,4 +1,6 @@
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use move_binary_format::CompiledModule;
+use move_core_types::language_storage::{ModuleId, StructTag};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
@@ -8,15 +10,23 @@ use sui_json_rpc_types::{ObjectChange, SuiTransactionBlockResponse, TransactionF
 use sui_types::{
     base_types::{ObjectID, SequenceNumber},
     digests::TransactionDigest,
+    display::DisplayVersionUpdatedEvent,
     in_memory_storage::InMemoryStorage,
     inner_temporary_store::InnerTemporaryStore,
     object::{Object, Owner, PastObjectRead},
     transaction::TransactionData,
 };
 
-use crate::sandbox::storage::{indices::TransactionIndices, snapshot::StorageSnapshot};
+use crate::sandbox::storage::{
+    debug_info::{DebugInfo, ModuleSource},
+    indices::TransactionIndices,
+    registry::PackageRegistry,
+    snapshot::StorageSnapshot,
+};
 
+pub mod debug_info;
 mod indices;
+mod registry;
 mod snapshot;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -32,6 +42,9 @@ pub struct StorageExtension {
     timelines: HashMap<ObjectID, ObjectTimeline>,
     transactions: HashMap<TransactionDigest, SuiTransactionBlockResponse>,
     transaction_indices: TransactionIndices,
+    displays: HashMap<StructTag, DisplayVersionUpdatedEvent>,
+    package_registry: PackageRegistry,
+    debug_info: DebugInfo,
     checkpoint: u64,
 }
 
@@ -44,6 +57,9 @@ impl StorageExtension {
             timelines: Default::default(),
             transactions: Default::default(),
             transaction_indices: TransactionIndices::new(),
+            displays: Default::default(),
+            package_registry: Default::default(),
+            debug_info: Default::default(),
             checkpoint: 0,
         }
     }
@@ -56,6 +72,70 @@ impl StorageExtension {
         self.checkpoint += 1;
     }
 
+    pub fn display_for(&self, object_type: &StructTag) -> Option<&DisplayVersionUpdatedEvent> {
+        self.displays.get(object_type)
+    }
+
+    pub fn set_display(&mut self, object_type: StructTag, display: DisplayVersionUpdatedEvent) {
+        self.displays.insert(object_type, display);
+    }
+
+    pub fn package_registry(&self) -> &PackageRegistry {
+        &self.package_registry
+    }
+
+    pub fn package_registry_mut(&mut self) -> &mut PackageRegistry {
+        &mut self.package_registry
+    }
+
+    pub fn debug_info(&self) -> &DebugInfo {
+        &self.debug_info
+    }
+
+    pub fn debug_info_mut(&mut self) -> &mut DebugInfo {
+        &mut self.debug_info
+    }
+
+    // all known versions of a package, keyed by version, given the id of any of them
+    pub fn package_versions(&self, package_id: &ObjectID) -> BTreeMap<SequenceNumber, ObjectID> {
+        self.package_registry
+            .versions(&self.original_package_id(package_id))
+    }
+
+    // the module of exactly the package version at the module address
+    pub fn module_at(&self, module: &ModuleId) -> Option<CompiledModule> {
+        let bytes = self
+            .get_object(&ObjectID::from(*module.address()))?
+            .data
+            .try_as_package()?
+            .serialized_module_map()
+            .get(module.name().as_str())?;
+
+        CompiledModule::deserialize_with_defaults(bytes).ok()
+    }
+
+    // sources are registered under the package version they were published with
+    pub fn module_source(&self, module: &ModuleId) -> Option<&ModuleSource> {
+        let package = self.latest_package_id(&ObjectID::from(*module.address()));
+
+        self.debug_info
+            .module_source(&ModuleId::new(package.into(), module.name().to_owned()))
+            .or_else(|| self.debug_info.module_source(module))
+    }
+
+    fn latest_package_id(&self, package_id: &ObjectID) -> ObjectID {
+        self.package_registry
+            .latest(package_id)
+            .unwrap_or(*package_id)
+    }
+
+    pub fn original_package_id(&self, package_id: &ObjectID) -> ObjectID {
+        self.get_object(package_id)
+            .and_then(|object| object.data.try_as_package())
+            .map(|package| package.original_package_id())
+            .unwrap_or(*package_id)
+    }
+
     pub fn as_inner(&self) -> &InMemoryStorage {
         &self.inner
     }
@@ -266,15 +346,19 @@ impl StorageExtension {
             match change {
                 ObjectChange::Created { object_id, .. }
                 | ObjectChange::Mutated { object_id, .. }
-                | ObjectChange::Transferred { object_id, .. }
-                | ObjectChange::Published {
-                    package_id: object_id,
-                    ..
-                } => {
+                | ObjectChange::Transferred { object_id, .. } => {
                     if let Some(object) = temporary_store.written.get(object_id) {
                         self.insert_object(object.clone());
                     }
                 }
+                ObjectChange::Published { package_id, .. } => {
+                    if let Some(object) = temporary_store.written.get(package_id) {
+                        if let Some(package) = object.data.try_as_package() {
+                            self.package_registry.track_package(package);
+                        }
+                        self.insert_object(object.clone());
+                    }
+                }
                 ObjectChange::Wrapped { object_id, .. } => {
                     if let Some(object) = temporary_store.written.get(object_id) {
                         self.wrap_object(*object_id, object.clone());
@@ -301,7 +385,7 @@ impl StorageExtension {
         }
     }
 
-    pub fn to_snapshot(&self) -> StorageSnapshot {
+    pub fn to_snapshot(&self, include_debug_info: bool) -> StorageSnapshot {
         StorageSnapshot {
             objects: self.inner.objects().clone(),
             address_objects: self.address_objects.clone(),
@@ -309,6 +393,9 @@ impl StorageExtension {
             timelines: self.timelines.clone(),
             transactions: self.transactions.clone(),
             transaction_indices: self.transaction_indices.clone(),
+            displays: self.displays.clone(),
+            package_registry: self.package_registry.clone(),
+            debug_info: include_debug_info.then(|| self.debug_info.clone()),
             checkpoint: self.checkpoint,
         }
     }
@@ -327,6 +414,9 @@ impl StorageExtension {
             timelines: snapshot.timelines,
             transactions: snapshot.transactions,
             transaction_indices: snapshot.transaction_indices,
+            displays: snapshot.displays,
+            package_registry: snapshot.package_registry,
+            debug_info: snapshot.debug_info.unwrap_or_default(),
             checkpoint: snapshot.checkpoint,
         }
     }
@@ -344,14 +434,19 @@ impl StorageExtension {
         self.timelines = snapshot.timelines;
         self.transactions = snapshot.transactions;
         self.transaction_indices = snapshot.transaction_indices;
+        self.displays = snapshot.displays;
+        self.package_registry = snapshot.package_registry;
         self.checkpoint = snapshot.checkpoint;
-    }
 
-    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
-        let snapshot = self.to_snapshot();
-        let bytes = bcs::to_bytes(&snapshot)?;
+        // sources stay valid for the packages they were published with, so snapshots
+        // taken without debug info keep the current one
+        if let Some(debug_info) = snapshot.debug_info {
+            self.debug_info = debug_info;
+        }
+    }
 
-        Ok(bytes)
+    pub fn to_bytes(&self, include_debug_info: bool) -> anyhow::Result<Vec<u8>> {
+        self.to_snapshot(include_debug_info).to_bytes()
     }
 
     pub fn restone_from_bytes(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
@@ -360,15 +455,15 @@ impl StorageExtension {
 
         decoder.read_to_end(&mut decompressed)?;
 
-        let snapshot: StorageSnapshot = bcs::from_bytes(&decompressed)?;
+        let snapshot = StorageSnapshot::from_bytes(&decompressed)?;
 
         self.restore_from_snapshot(snapshot);
 
         Ok(())
     }
 
-    pub fn to_bytes_compressed(&self) -> anyhow::Result<Vec<u8>> {
-        let bytes = self.to_bytes()?;
+    pub fn to_bytes_compressed(&self, include_debug_info: bool) -> anyhow::Result<Vec<u8>> {
+        let bytes = self.to_bytes(include_debug_info)?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
 
         encoder.write_all(&bytes)?;
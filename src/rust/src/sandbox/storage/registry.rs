@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    move_package::MovePackage,
+};
+
+// Keeps every published package version grouped by the original package id,
+// plus human readable names pointing at those original ids.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct PackageRegistry {
+    names: BTreeMap<String, ObjectID>,
+    versions: HashMap<ObjectID, BTreeMap<SequenceNumber, ObjectID>>,
+}
+
+impl PackageRegistry {
+    pub fn track_package(&mut self, package: &MovePackage) {
+        self.versions
+            .entry(package.original_package_id())
+            .or_default()
+            .insert(package.version(), package.id());
+    }
+
+    pub fn register_name(&mut self, name: String, original_id: ObjectID) {
+        self.names.insert(name, original_id);
+    }
+
+    pub fn names(&self) -> &BTreeMap<String, ObjectID> {
+        &self.names
+    }
+
+    pub fn original_id(&self, name: &str) -> Option<ObjectID> {
+        self.names.get(name).copied()
+    }
+
+    pub fn versions(&self, original_id: &ObjectID) -> BTreeMap<SequenceNumber, ObjectID> {
+        self.versions.get(original_id).cloned().unwrap_or_default()
+    }
+
+    pub fn latest(&self, original_id: &ObjectID) -> Option<ObjectID> {
+        self.versions
+            .get(original_id)
+            .and_then(|versions| versions.values().last())
+            .copied()
+    }
+}
@@ -1,14 +1,23 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::sandbox::storage::{indices::TransactionIndices, ObjectTimeline};
+use crate::sandbox::storage::{
+    debug_info::DebugInfo, indices::TransactionIndices, registry::PackageRegistry, ObjectTimeline,
+};
+use move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 use sui_types::{
     base_types::ObjectID,
     digests::TransactionDigest,
+    display::DisplayVersionUpdatedEvent,
     object::{Object, Owner},
 };
 
+// versioned snapshots start with this header and the format version, snapshots taken
+// before the format was versioned have no header at all
+const SNAPSHOT_MAGIC: &[u8] = b"SUISANDBOX";
+const SNAPSHOT_VERSION: u8 = 1;
+
 #[derive(Serialize, Deserialize)]
 pub struct StorageSnapshot {
     pub objects: BTreeMap<ObjectID, Object>,
@@ -17,5 +26,70 @@ pub struct StorageSnapshot {
     pub timelines: HashMap<ObjectID, ObjectTimeline>,
     pub transactions: HashMap<TransactionDigest, SuiTransactionBlockResponse>,
     pub transaction_indices: TransactionIndices,
+    pub displays: HashMap<StructTag, DisplayVersionUpdatedEvent>,
+    pub package_registry: PackageRegistry,
+    // only included on request, sources and abort stacks make up most of a snapshot
+    pub debug_info: Option<DebugInfo>,
     pub checkpoint: u64,
 }
+
+// the layout of snapshots taken before the format was versioned
+#[derive(Deserialize)]
+struct LegacyStorageSnapshot {
+    objects: BTreeMap<ObjectID, Object>,
+    address_objects: HashMap<Owner, HashSet<ObjectID>>,
+    object_addresses: HashMap<ObjectID, Owner>,
+    timelines: HashMap<ObjectID, ObjectTimeline>,
+    transactions: HashMap<TransactionDigest, SuiTransactionBlockResponse>,
+    transaction_indices: TransactionIndices,
+    checkpoint: u64,
+}
+
+impl From<LegacyStorageSnapshot> for StorageSnapshot {
+    fn from(legacy: LegacyStorageSnapshot) -> Self {
+        // package versions can be recovered from the objects, names and displays can't
+        let mut package_registry = PackageRegistry::default();
+
+        for object in legacy.objects.values() {
+            if let Some(package) = object.data.try_as_package() {
+                package_registry.track_package(package);
+            }
+        }
+
+        Self {
+            objects: legacy.objects,
+            address_objects: legacy.address_objects,
+            object_addresses: legacy.object_addresses,
+            timelines: legacy.timelines,
+            transactions: legacy.transactions,
+            transaction_indices: legacy.transaction_indices,
+            displays: HashMap::new(),
+            package_registry,
+            debug_info: None,
+            checkpoint: legacy.checkpoint,
+        }
+    }
+}
+
+impl StorageSnapshot {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+
+        bytes.push(SNAPSHOT_VERSION);
+        bytes.extend(bcs::to_bytes(self)?);
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let Some(versioned) = bytes.strip_prefix(SNAPSHOT_MAGIC) else {
+            return Ok(bcs::from_bytes::<LegacyStorageSnapshot>(bytes)?.into());
+        };
+
+        match versioned.split_first() {
+            Some((&SNAPSHOT_VERSION, snapshot)) => Ok(bcs::from_bytes(snapshot)?),
+            Some((version, _)) => anyhow::bail!("Unsupported snapshot version {version}"),
+            None => anyhow::bail!("Snapshot is missing its format version"),
+        }
+    }
+}
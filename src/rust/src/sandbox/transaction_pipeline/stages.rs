@@ -1,24 +1,34 @@
 use std::sync::Arc;
 
+use move_trace_format::format::{MoveTrace, MoveTraceBuilder};
 use sui_json_rpc_types::{
     BalanceChange, ObjectChange, SuiTransactionBlockEvents, SuiTransactionBlockResponse,
 };
 use sui_types::{
     crypto::Signature,
-    effects::TransactionEffects,
+    effects::{TransactionEffects, TransactionEffectsAPI},
     error::ExecutionError,
+    execution_status::ExecutionStatus,
     gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
     metrics::LimitsMetrics,
     signature::GenericSignature,
     transaction::{
-        CheckedInputObjects, SenderSignedData, Transaction, TransactionData, TransactionDataAPI,
+        CheckedInputObjects, GasData, SenderSignedData, Transaction, TransactionData,
+        TransactionDataAPI,
     },
 };
 
 use crate::{
     sandbox::{
-        extensions::changes::Changes,
+        extensions::{
+            changes::Changes,
+            display::DisplayExtension,
+            gas_mode::GasMode,
+            gas_payment::check_gas_payment,
+            input_validation::{check_gas_balance, check_input_objects, check_transaction},
+            stack_traces::StackTraceExtension,
+        },
         storage::StorageExtension,
         transaction_pipeline::{PipelineResult, TransactionStage},
         MoveVMSandbox,
@@ -26,16 +36,32 @@ use crate::{
     utils::response_from_errors,
 };
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    Execution,
+    // only validates and locks the owned inputs, the transaction is executed later; a pending
+    // `reject_next_tx` is left for the execution and the faucet doesn't fund it
+    Locking,
+    // doesn't lock objects or use up congestion budgets
+    DryRun,
+}
+
 pub struct ValidationInput {
     pub tx_data: TransactionData,
     pub signatures: Vec<Signature>,
+    pub mode: ValidationMode,
+    // returns the Move trace of the execution to the caller
+    pub trace: bool,
 }
 
 pub struct ExecutionInput {
     pub tx_data: TransactionData,
     pub transaction: Transaction,
     pub checked_objects: CheckedInputObjects,
+    // differs from the signed gas data when the gas mode funds the payment
+    pub gas_data: GasData,
     pub gas_status: SuiGasStatus,
+    pub trace: bool,
 }
 
 pub struct EffectsInput {
@@ -44,6 +70,7 @@ pub struct EffectsInput {
     pub temporary_store: InnerTemporaryStore,
     pub effects: TransactionEffects,
     pub execution_result: Result<(), ExecutionError>,
+    pub trace: Option<MoveTrace>,
 }
 
 pub struct StorageInput {
@@ -55,6 +82,7 @@ pub struct StorageInput {
     pub object_changes: Vec<ObjectChange>,
     pub balance_changes: Vec<BalanceChange>,
     pub execution_result: Result<(), ExecutionError>,
+    pub trace: Option<MoveTrace>,
 }
 
 pub struct TransactionOutput {
@@ -65,6 +93,7 @@ pub struct TransactionOutput {
     pub object_changes: Vec<ObjectChange>,
     pub balance_changes: Vec<BalanceChange>,
     pub execution_result: Result<(), ExecutionError>,
+    pub trace: Option<MoveTrace>,
 }
 
 #[derive(Debug)]
@@ -80,6 +109,19 @@ pub struct DryRunOutput {
 pub struct ValidationStage;
 
 impl ValidationStage {
+    fn reject(
+        &self,
+        tx_data: TransactionData,
+        transaction: Transaction,
+        error: impl ToString,
+        storage: &StorageExtension,
+    ) -> anyhow::Result<PipelineResult<ExecutionInput>> {
+        let response =
+            self.create_error_response(&tx_data, transaction, error.to_string(), storage)?;
+
+        Ok(PipelineResult::EarlyReturn(response))
+    }
+
     fn create_error_response(
         &self,
         tx_data: &TransactionData,
@@ -115,63 +157,137 @@ impl TransactionStage for ValidationStage {
                 .collect(),
         ));
 
-        if let Some(response) = sandbox.transaction_control.reject(|reason| {
-            self.create_error_response(
-                &input.tx_data,
-                transaction.clone(),
-                reason,
-                &sandbox.storage,
-            )
-        }) {
-            let response = response?;
+        if input.mode != ValidationMode::Locking {
+            if let Some(response) = sandbox.transaction_control.reject(|reason| {
+                self.create_error_response(
+                    &input.tx_data,
+                    transaction.clone(),
+                    reason,
+                    &sandbox.storage,
+                )
+            }) {
+                let response = response?;
 
-            return Ok(PipelineResult::EarlyReturn(response));
+                return Ok(PipelineResult::EarlyReturn(response));
+            }
         }
 
         if let Err(e) = sandbox
             .auth_extension
             .verify_transaction(&transaction, sandbox.epoch)
         {
-            let response = self.create_error_response(
-                &input.tx_data,
-                transaction,
-                e.to_string(),
-                &sandbox.storage,
-            )?;
+            return self.reject(input.tx_data, transaction, e, &sandbox.storage);
+        }
+
+        let checks =
+            check_transaction(&transaction, &input.tx_data, sandbox.epoch, &sandbox.config)
+                .and_then(|_| check_input_objects(&input.tx_data, &sandbox.storage));
 
-            return Ok(PipelineResult::EarlyReturn(response));
+        if let Err(e) = checks {
+            return self.reject(input.tx_data, transaction, e, &sandbox.storage);
         }
 
-        match sandbox.storage.get_checked_objects(
+        if let Err(e) = check_gas_payment(
+            &input.tx_data,
+            input.tx_data.gas_data(),
+            sandbox.gas_mode,
+            &sandbox.storage,
+            &sandbox.config,
+        ) {
+            return self.reject(input.tx_data, transaction, e, &sandbox.storage);
+        }
+
+        let faucet_coin = sandbox
+            .gas_mode
+            .faucet_coin(input.tx_data.gas_data(), &sandbox.storage);
+
+        let mut gas_data = input.tx_data.gas_data().clone();
+        if let Some(coin) = &faucet_coin {
+            gas_data.payment.push(coin.compute_object_reference());
+        }
+
+        let mut checked = match sandbox.storage.get_checked_objects(
             &input.tx_data,
             Some(&input.signatures),
             &sandbox.auth_extension,
+            &[],
         ) {
-            Ok(checked) => {
-                let gas_status = SuiGasStatus::new(
-                    input.tx_data.gas_data().budget,
-                    input.tx_data.gas_data().price,
-                    sandbox.reference_price,
-                    &sandbox.config,
-                )?;
-
-                Ok(PipelineResult::Continue(ExecutionInput {
-                    tx_data: input.tx_data,
-                    transaction,
-                    checked_objects: checked,
-                    gas_status,
-                }))
+            Ok(checked) => checked,
+            Err(e) => return self.reject(input.tx_data, transaction, e, &sandbox.storage),
+        };
+
+        let gas_status =
+            match sandbox
+                .gas_mode
+                .gas_status(&gas_data, sandbox.reference_price, &sandbox.config)
+            {
+                Ok(gas_status) => gas_status,
+                Err(e) => return self.reject(input.tx_data, transaction, e, &sandbox.storage),
+            };
+
+        if sandbox.gas_mode != GasMode::Unmetered {
+            if let Err(e) = check_gas_balance(
+                &gas_status,
+                &gas_data,
+                faucet_coin.as_ref(),
+                &sandbox.storage,
+            ) {
+                return self.reject(input.tx_data, transaction, e, &sandbox.storage);
             }
-            Err(e) => {
-                let response = self.create_error_response(
-                    &input.tx_data,
-                    transaction,
-                    e.to_string(),
-                    &sandbox.storage,
-                )?;
-                Ok(PipelineResult::EarlyReturn(response))
+        }
+
+        if input.mode != ValidationMode::DryRun {
+            if let Err(e) = sandbox
+                .object_locks
+                .acquire(&input.tx_data, &sandbox.storage)
+            {
+                return self.reject(input.tx_data, transaction, e, &sandbox.storage);
+            }
+        }
+
+        if input.mode == ValidationMode::Execution {
+            let congested = sandbox
+                .congestion
+                .schedule(&input.tx_data, sandbox.storage.checkpoint());
+
+            // the faucet only pays for transactions that passed validation
+            let funded = faucet_coin.is_some();
+            if let Some(coin) = faucet_coin {
+                sandbox.storage.insert_object(coin);
+            }
+
+            // a cancelled transaction is still executed, without access to its shared objects
+            if funded || !congested.is_empty() {
+                let mut execution_data = input.tx_data.clone();
+                *execution_data.gas_data_mut() = gas_data.clone();
+
+                checked = match sandbox.storage.get_checked_objects(
+                    &execution_data,
+                    None,
+                    &sandbox.auth_extension,
+                    &congested,
+                ) {
+                    Ok(checked) => checked,
+                    Err(e) => return self.reject(input.tx_data, transaction, e, &sandbox.storage),
+                };
             }
         }
+
+        // instruction tracing is expensive, only run it when the trace is asked for or recorded
+        let trace = input.trace
+            || (input.mode == ValidationMode::Execution
+                && (sandbox.coverage.is_enabled()
+                    || sandbox.gas_profiler.is_enabled()
+                    || sandbox.abort_stack_traces));
+
+        Ok(PipelineResult::Continue(ExecutionInput {
+            tx_data: input.tx_data,
+            transaction,
+            checked_objects: checked,
+            gas_data,
+            gas_status,
+            trace,
+        }))
     }
 }
 
@@ -186,6 +302,8 @@ impl TransactionStage for ExecutionStage {
         input: Self::Input,
         sandbox: &mut MoveVMSandbox,
     ) -> anyhow::Result<PipelineResult<Self::Output>> {
+        let mut trace_builder = input.trace.then(MoveTraceBuilder::new);
+
         let (temporary_store, _, effects, _, execution_result) =
             sandbox.executor.execute_transaction_to_effects(
                 &sandbox.storage.as_inner(),
@@ -196,20 +314,35 @@ impl TransactionStage for ExecutionStage {
                 &sandbox.epoch,
                 sandbox.clock().get_time(),
                 input.checked_objects,
-                input.tx_data.gas_data().clone(),
+                input.gas_data,
                 input.gas_status,
                 input.tx_data.kind().clone(),
                 input.tx_data.sender(),
                 input.tx_data.digest(),
-                &mut None,
+                &mut trace_builder,
             );
 
+        let trace = trace_builder.map(MoveTraceBuilder::into_trace);
+
+        if let Some(trace) = &trace {
+            if sandbox.gas_profiler.is_enabled() {
+                sandbox.gas_profiler.record(
+                    &input.tx_data,
+                    trace,
+                    &temporary_store,
+                    &effects,
+                    &sandbox.storage,
+                );
+            }
+        }
+
         Ok(PipelineResult::Continue(EffectsInput {
             tx_data: input.tx_data,
             transaction: input.transaction,
             temporary_store,
             effects,
             execution_result,
+            trace,
         }))
     }
 }
@@ -251,6 +384,7 @@ impl TransactionStage for EffectsStage {
             object_changes,
             balance_changes,
             execution_result: input.execution_result,
+            trace: input.trace,
         }))
     }
 }
@@ -266,11 +400,35 @@ impl TransactionStage for StorageStage {
         input: Self::Input,
         sandbox: &mut MoveVMSandbox,
     ) -> anyhow::Result<PipelineResult<Self::Output>> {
+        // the locked versions are consumed by the transaction, whether it succeeded or not
+        sandbox
+            .object_locks
+            .release(input.effects.transaction_digest());
+
         sandbox.storage.apply_transaction_effects(
             input.transaction.data().transaction_data(),
             &input.object_changes,
             &input.temporary_store,
         );
+        sandbox
+            .storage
+            .track_display_updates(&input.temporary_store.events.data);
+
+        if let Some(trace) = &input.trace {
+            if sandbox.coverage.is_enabled() {
+                sandbox.coverage.record(trace);
+            }
+        }
+
+        if let (ExecutionStatus::Failure { .. }, Some(trace)) =
+            (input.effects.status(), &input.trace)
+        {
+            if sandbox.abort_stack_traces {
+                sandbox
+                    .storage
+                    .record_abort_stack(*input.effects.transaction_digest(), trace);
+            }
+        }
 
         Ok(PipelineResult::Continue(TransactionOutput {
             tx_data: input.tx_data,
@@ -280,6 +438,7 @@ impl TransactionStage for StorageStage {
             object_changes: input.object_changes,
             balance_changes: input.balance_changes,
             execution_result: input.execution_result,
+            trace: input.trace,
         }))
     }
 }
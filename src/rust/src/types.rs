@@ -1,10 +1,19 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
-use sui_json_rpc_types::{SuiTransactionBlockResponseOptions, TransactionFilter};
+use sui_json_rpc_types::{
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions, TransactionFilter,
+};
 use sui_types::{
     base_types::{ObjectID, SequenceNumber},
+    digests::TransactionDigest,
     dynamic_field::DynamicFieldName,
+    gas::GasCostSummary,
+    object::Owner,
 };
 
+use crate::sandbox::{CongestionConfig, GasMode};
+
 // types I did not found in sui code.
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -32,3 +41,202 @@ pub struct QueryTransactionBlocksParams {
     pub filter: TransactionFilter,
     pub options: SuiTransactionBlockResponseOptions,
 }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateObjectParams {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    pub owner: Owner,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishFromPathResult {
+    pub package_id: ObjectID,
+    pub named_addresses: BTreeMap<String, ObjectID>,
+    pub response: SuiTransactionBlockResponse,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PublishOptions {
+    pub auto_fund: bool,
+    pub gas_budget: Option<u64>,
+    pub upgradeable: bool,
+    // registers the published package under this name
+    pub name: Option<String>,
+    // used to map aborts back to source lines
+    pub source_maps: Vec<ModuleSourceMap>,
+    pub sources: Vec<SourceFile>,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            auto_fund: true,
+            gas_budget: None,
+            upgradeable: true,
+            name: None,
+            source_maps: vec![],
+            sources: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleSourceMap {
+    pub module: String,
+    // base64 encoded `.mvsm` file from the build directory
+    pub source_map: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceFile {
+    pub path: String,
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedPackage {
+    pub name: String,
+    pub original_id: ObjectID,
+    pub latest_id: ObjectID,
+    pub versions: BTreeMap<SequenceNumber, ObjectID>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SandboxOptions {
+    pub protocol_version: Option<u64>,
+    pub framework_packages: Vec<FrameworkPackage>,
+    pub gas_mode: Option<GasMode>,
+    pub congestion: Option<CongestionConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameworkPackage {
+    pub id: ObjectID,
+    // base64 encoded modules, as printed by `sui move build --dump-bytecode-as-base64`
+    pub modules: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishDiagnostic {
+    pub module: Option<String>,
+    pub function: Option<String>,
+    // verifier pass that rejected the module, e.g. `linkage`, `ability` or `reference safety`
+    pub check: String,
+    pub status: String,
+    pub status_code: Option<u64>,
+    pub code_offset: Option<u16>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveAbort {
+    pub package: ObjectID,
+    pub module: String,
+    pub function: Option<String>,
+    pub instruction: u16,
+    // as a string, `#[error]` abort codes don't fit into a JS number
+    #[serde(serialize_with = "serialize_to_string")]
+    pub code: u64,
+    // set when the abort code comes from an `#[error]` constant
+    pub error_name: Option<String>,
+    pub line: Option<u16>,
+    pub message: Option<String>,
+    // innermost frame first
+    pub stack: Vec<StackFrame>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+    pub package: ObjectID,
+    pub module: String,
+    pub function: String,
+    pub pc: Option<u16>,
+    // only known when source maps were supplied at publish time
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WithAbort<T> {
+    #[serde(flatten)]
+    pub inner: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abort: Option<MoveAbort>,
+}
+
+// `response` is missing when the transaction couldn't be executed and went back to the mempool
+#[derive(Debug, Clone, Serialize)]
+pub struct SealedTransaction<T> {
+    pub digest: TransactionDigest,
+    pub response: Option<T>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TracedExecution<T> {
+    pub response: T,
+    // base64 encoded zstd compressed json, the `.json.zst` format read by the Move trace debugger
+    pub trace: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    pub gas_used: GasCostSummary,
+    // sorted by inclusive gas, most expensive first
+    pub functions: Vec<FunctionGas>,
+    pub commands: Vec<CommandGas>,
+    pub storage: Vec<ObjectStorageGas>,
+    // one `frame;frame;frame gas` line per call path, as read by flamegraph tools
+    pub folded_stacks: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionGas {
+    pub function: String,
+    pub calls: u64,
+    pub inclusive: u64,
+    pub exclusive: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandGas {
+    pub index: u16,
+    pub kind: String,
+    pub function: Option<String>,
+    // only Move calls and package `init` functions are metered per instruction
+    pub computation_units: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectStorageGas {
+    pub object_id: ObjectID,
+    pub object_type: Option<String>,
+    pub bytes_written: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+}
+
+fn serialize_to_string<S: serde::Serializer>(
+    value: &u64,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
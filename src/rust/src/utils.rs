@@ -52,6 +52,10 @@ pub fn decode_base64(data: &str) -> Result<Vec<u8>> {
         .map_err(|e| Error::from_reason(format!("Base64 decode failed: {}", e)))
 }
 
+pub fn encode_base64(data: &[u8]) -> String {
+    general_purpose::STANDARD.encode(data)
+}
+
 pub fn deserialize_bcs<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
     bcs::from_bytes(bytes)
         .map_err(|e| Error::from_reason(format!("BCS deserialization failed: {}", e)))
@@ -68,6 +72,11 @@ pub fn parse_signature(signature_base64: &str) -> Result<Signature> {
         .map_err(|e| Error::from_reason(format!("Signature parsing failed: {}", e)))
 }
 
+pub fn parse_struct_tag(type_str: &str) -> Result<StructTag> {
+    StructTag::from_str(type_str)
+        .map_err(|e| Error::from_reason(format!("Invalid struct tag: {} - {}", type_str, e)))
+}
+
 pub fn parse_optional_type_tag(type_str: Option<String>) -> Option<TypeTag> {
     type_str.map(|st| {
         TypeTag::Struct(Box::new(
@@ -1,27 +1,231 @@
 import {
   DryRunTransactionBlockResponse,
+  GasCostSummary,
   GetDynamicFieldObjectParams,
   GetDynamicFieldsParams,
+  GetMoveFunctionArgTypesParams,
   GetNormalizedMoveFunctionParams,
+  GetNormalizedMoveModuleParams,
+  GetNormalizedMoveModulesByPackageParams,
+  GetNormalizedMoveStructParams,
   ObjectRead,
   PaginatedTransactionResponse,
   QueryTransactionBlocksParams,
+  SuiMoveFunctionArgType,
+  SuiMoveNormalizedModule,
+  SuiMoveNormalizedModules,
+  SuiMoveNormalizedStruct,
+  SuiObjectDataOptions,
   SuiObjectResponse,
   SuiTransactionBlockResponse,
   TryGetPastObjectParams,
 } from '@mysten/sui/client'
+import { writeFileSync } from 'fs'
 import { SuiSandbox } from '../../index'
 
+export type SandboxOwner =
+  | { AddressOwner: string }
+  | { ObjectOwner: string }
+  | { Shared: { initial_shared_version: number } }
+  | 'Immutable'
+
+export interface CreateObjectParams {
+  type: string
+  fields: Record<string, unknown>
+  owner: SandboxOwner
+}
+
+export interface PublishFromPathResult {
+  packageId: string
+  namedAddresses: Record<string, string>
+  response: SuiTransactionBlockResponse
+}
+
+export interface PublishOptions {
+  autoFund?: boolean
+  gasBudget?: number
+  upgradeable?: boolean
+  name?: string
+  // base64 encoded `.mvsm` files and the Move sources they point to, used for abort stack traces
+  sourceMaps?: { module: string; sourceMap: string }[]
+  sources?: { path: string; contents: string }[]
+}
+
+export interface PublishDiagnostic {
+  module: string | null
+  function: string | null
+  // verifier pass that rejected the module, e.g. 'linkage', 'ability' or 'reference safety'
+  check: string
+  status: string
+  statusCode: number | null
+  codeOffset: number | null
+  message: string | null
+}
+
+export class PublishError extends Error {
+  constructor(public readonly diagnostics: PublishDiagnostic[]) {
+    const lines = diagnostics.map((d) => {
+      const location = [d.module, d.function].filter(Boolean).join('::') || 'package'
+
+      return `${location}: ${d.check} check failed with ${d.status}`
+    })
+
+    super(`Package verification failed:\n${lines.join('\n')}`)
+    this.name = 'PublishError'
+  }
+}
+
+// the native module reports failed verification as this prefix followed by the diagnostics as JSON
+const PUBLISH_DIAGNOSTICS_PREFIX = 'PublishVerificationError:'
+
+function withPublishDiagnostics<T>(action: () => T): T {
+  try {
+    return action()
+  } catch (e) {
+    if (e instanceof Error && e.message.startsWith(PUBLISH_DIAGNOSTICS_PREFIX)) {
+      throw new PublishError(JSON.parse(e.message.slice(PUBLISH_DIAGNOSTICS_PREFIX.length)))
+    }
+
+    throw e
+  }
+}
+
+export interface MoveAbort {
+  package: string
+  module: string
+  function: string | null
+  instruction: number
+  // decimal string, `#[error]` constants abort with a bitset above Number.MAX_SAFE_INTEGER
+  code: string
+  codeBigInt: bigint
+  errorName: string | null
+  line: number | null
+  message: string | null
+  // innermost frame first
+  stack: MoveStackFrame[]
+}
+
+export interface MoveStackFrame {
+  package: string
+  module: string
+  function: string
+  pc: number | null
+  file: string | null
+  line: number | null
+  column: number | null
+}
+
+export function formatMoveStackTrace(abort: MoveAbort) {
+  const frames = abort.stack.map((frame) => {
+    const location = frame.file ? `${frame.file}:${frame.line}:${frame.column}` : `pc ${frame.pc ?? '?'}`
+
+    return `    at ${frame.module}::${frame.function} (${location})`
+  })
+
+  const header = `MoveAbort ${abort.errorName ?? abort.code}${abort.message ? `: ${abort.message}` : ''}`
+
+  return [header, ...frames].join('\n')
+}
+
+export type SandboxTransactionResponse = SuiTransactionBlockResponse & { abort?: MoveAbort }
+
+function withAbortCode<T extends SandboxTransactionResponse | null>(response: T): T {
+  if (response?.abort) {
+    response.abort.codeBigInt = BigInt(response.abort.code)
+  }
+
+  return response
+}
+
+export interface SignedTransactionInput {
+  transactionBlock: Uint8Array | string
+  signature: string | string[]
+}
+
+// the base64 transaction bytes and the signatures the transaction API takes
+function transactionArgs(input: SignedTransactionInput): [string, string[]] {
+  const txBytes =
+    typeof input.transactionBlock === 'string'
+      ? input.transactionBlock
+      : Buffer.from(input.transactionBlock).toString('base64')
+
+  return [txBytes, Array.isArray(input.signature) ? input.signature : [input.signature]]
+}
+
+// gas units as metered by the Move VM, `foldedStacks` can be fed to flamegraph tools
+export interface GasProfile {
+  gasUsed: GasCostSummary
+  functions: { function: string; calls: number; inclusive: number; exclusive: number }[]
+  commands: { index: number; kind: string; function: string | null; computationUnits: number | null }[]
+  storage: GasProfileStorage[]
+  foldedStacks: string
+}
+
+export interface GasProfileStorage {
+  objectId: string
+  objectType: string | null
+  bytesWritten: number
+  storageCost: number
+  storageRebate: number
+}
+
+export interface ResolvedPackage {
+  name: string
+  originalId: string
+  latestId: string
+  versions: Record<string, string>
+}
+
+export type UpgradePolicy = 'compatible' | 'additive' | 'dep_only'
+
 export interface SandboxConfig {
   initialBalance?: bigint
   enableLogging?: boolean
 }
 
+export interface FrameworkPackage {
+  id: string
+  // base64 encoded modules
+  modules: string[]
+}
+
+// `unmetered` needs no gas coin and charges nothing, `metered-but-sponsored-by-faucet` mints whatever
+// the gas payment is missing to cover the budget
+export type GasMode = 'unmetered' | 'metered' | 'metered-but-sponsored-by-faucet'
+
+export interface CongestionConfig {
+  // `txCount` charges every transaction 1, `gasBudget` charges it its gas budget
+  mode: 'txCount' | 'gasBudget'
+  // budget of every mutably used shared object per checkpoint
+  perObjectBudget: number
+  // checkpoints a transaction can be deferred by before it is cancelled, 0 cancels right away; load over the
+  // budget carries over to the following checkpoints until they are sealed
+  maxDeferralCheckpoints?: number
+}
+
+// order of the transactions using shared objects in a sealed batch, `adversarial` lets every transaction front-run
+// the earlier submitted ones it conflicts with, highest gas price first
+export type OrderingStrategy = { type: 'fifo' } | { type: 'random'; seed: number } | { type: 'adversarial' }
+
+// `response` is null when the transaction couldn't be executed, it is pending again with the reason in `error`
+export interface SealedTransaction {
+  digest: string
+  response: SandboxTransactionResponse | null
+  error: string | null
+}
+
+export interface SandboxOptions {
+  protocolVersion?: number
+  frameworkPackages?: FrameworkPackage[]
+  gasMode?: GasMode
+  congestion?: CongestionConfig
+}
+
 export class SandboxClient {
   private sandbox: SuiSandbox
 
-  constructor() {
-    this.sandbox = new SuiSandbox()
+  constructor(options?: SandboxOptions) {
+    this.sandbox = new SuiSandbox(options ? JSON.stringify(options) : null)
   }
 
   coinApi() {
@@ -56,24 +260,92 @@ export class SandboxClient {
     return this.sandbox.storageApi()
   }
 
+  coverageApi() {
+    return this.sandbox.coverageApi()
+  }
+
+  enableCoverage() {
+    this.coverageApi().enable()
+  }
+
+  disableCoverage() {
+    this.coverageApi().disable()
+  }
+
+  resetCoverage() {
+    this.coverageApi().reset()
+  }
+
+  // `move` is the bcs `.mvcov` format read by `sui move coverage`, `lcov` needs source maps supplied at publish time
+  exportCoverage(format: 'lcov'): string
+  exportCoverage(format: 'move'): Uint8Array
+  exportCoverage(format: 'lcov' | 'move') {
+    return format === 'lcov'
+      ? this.coverageApi().exportLcov()
+      : Uint8Array.from(this.coverageApi().exportMoveCoverage())
+  }
+
+  gasProfilerApi() {
+    return this.sandbox.gasProfilerApi()
+  }
+
+  enableGasProfiler() {
+    this.gasProfilerApi().enable()
+  }
+
+  disableGasProfiler() {
+    this.gasProfilerApi().disable()
+  }
+
+  getGasProfile(digest: string): GasProfile | null {
+    return JSON.parse(this.gasProfilerApi().getProfile(digest))
+  }
+
   getCoins(address: string, coinType?: string | null) {
     return JSON.parse(this.coinApi().getCoins(address, coinType))
   }
 
-  executeTransactionBlock(input: {
-    transactionBlock: Uint8Array | string
-    signature: string | string[]
-  }): SuiTransactionBlockResponse {
-    const txBytes =
-      typeof input.transactionBlock === 'string'
-        ? input.transactionBlock
-        : Buffer.from(input.transactionBlock).toString('base64')
+  executeTransactionBlock(input: SignedTransactionInput): SandboxTransactionResponse {
+    const result = this.transactionApi().execute(...transactionArgs(input))
 
-    const signatures = Array.isArray(input.signature) ? input.signature : [input.signature]
+    return withAbortCode(JSON.parse(result))
+  }
 
-    const result = this.transactionApi().execute(txBytes, signatures)
+  // validates the transaction and locks its owned inputs without executing it, as a validator does when signing,
+  // any other transaction using the same object versions is rejected until this one is executed
+  lockTransactionBlock(input: SignedTransactionInput): string {
+    return this.transactionApi().lock(...transactionArgs(input))
+  }
 
-    return JSON.parse(result)
+  // locks the owned inputs and queues the transaction until the next `sealBatch` instead of executing it
+  submitTransactionBlock(input: SignedTransactionInput): string {
+    return this.transactionApi().submit(...transactionArgs(input))
+  }
+
+  pendingTransactions(): string[] {
+    return this.transactionApi().pending()
+  }
+
+  // executes the queued transactions, owned-only ones in submission order followed by the ones using shared objects
+  // in the order of `strategy`, and ends the checkpoint
+  sealBatch(strategy: OrderingStrategy = { type: 'fifo' }): SealedTransaction[] {
+    const results: SealedTransaction[] = JSON.parse(this.transactionApi().sealBatch(JSON.stringify(strategy)))
+
+    return results.map((result) => ({ ...result, response: withAbortCode(result.response) }))
+  }
+
+  // `trace` is the zstd compressed json read by the Move trace debugger, written to `traceFile` when given
+  executeTransactionBlockWithTrace(
+    input: SignedTransactionInput & { traceFile?: string },
+  ): { response: SandboxTransactionResponse; trace: Buffer | null } {
+    const result = JSON.parse(this.transactionApi().executeWithTrace(...transactionArgs(input)))
+    const trace = result.trace === null ? null : Buffer.from(result.trace, 'base64')
+
+    if (trace && input.traceFile) {
+      writeFileSync(input.traceFile, trace)
+    }
+
+    return { response: withAbortCode(result.response), trace }
   }
 
   dryRunTransaction(transactionBlock: Uint8Array | string): DryRunTransactionBlockResponse {
@@ -85,18 +357,22 @@ export class SandboxClient {
     return JSON.parse(result)
   }
 
-  getTransaction(digest: string): SuiTransactionBlockResponse {
+  getTransaction(digest: string): SandboxTransactionResponse {
     const response = this.transactionApi().getResponse(digest)
 
-    return JSON.parse(response)
+    return withAbortCode(JSON.parse(response))
   }
 
-  getObject(input: { id: string }) {
-    const result = this.objectApi().get(input.id)
+  getObject(input: { id: string; options?: SuiObjectDataOptions | null }) {
+    const result = this.objectApi().get(input.id, input.options ? JSON.stringify(input.options) : null)
 
     return JSON.parse(result)
   }
 
+  getDecoded(id: string, options: { depth?: number } = {}) {
+    return JSON.parse(this.objectApi().getDecoded(id, options.depth))
+  }
+
   advanceClockByMillis(millis: number) {
     this.clockApi().advanceByMillis(millis)
   }
@@ -109,12 +385,90 @@ export class SandboxClient {
     this.behaviourApi().setRejectNextTransaction(reason)
   }
 
+  setGasMode(mode: GasMode) {
+    this.behaviourApi().setGasMode(mode)
+  }
+
+  getGasMode() {
+    return this.behaviourApi().getGasMode() as GasMode
+  }
+
+  setCongestionControl(config: CongestionConfig | null) {
+    this.behaviourApi().setCongestionControl(config ? JSON.stringify(config) : null)
+  }
+
   mintSui(address: string, amount: number) {
     this.coinApi().mintSui(address, amount)
   }
 
-  publishPackage(modules: number[][], dependencies: string[], sender: string): SuiTransactionBlockResponse {
-    return JSON.parse(this.packageApi().publish(modules, dependencies, sender))
+  publishPackage(
+    modules: number[][],
+    dependencies: string[],
+    sender: string,
+    options?: PublishOptions,
+  ): SuiTransactionBlockResponse {
+    return JSON.parse(
+      withPublishDiagnostics(() =>
+        this.packageApi().publish(modules, dependencies, sender, options ? JSON.stringify(options) : null),
+      ),
+    )
+  }
+
+  verifyPackage(modules: number[][], dependencies: string[]): PublishDiagnostic[] {
+    return JSON.parse(this.packageApi().verify(modules, dependencies))
+  }
+
+  buildPublishTransaction(modules: number[][], dependencies: string[], sender: string, options?: PublishOptions) {
+    return withPublishDiagnostics(() =>
+      this.packageApi().buildPublishTransaction(
+        modules,
+        dependencies,
+        sender,
+        options ? JSON.stringify(options) : null,
+      ),
+    )
+  }
+
+  publishFromPath(dir: string, sender: string): PublishFromPathResult {
+    return JSON.parse(withPublishDiagnostics(() => this.packageApi().publishFromPath(dir, sender)))
+  }
+
+  upgradePackage(
+    packageId: string,
+    upgradeCapId: string,
+    modules: number[][],
+    dependencies: string[],
+    policy: UpgradePolicy = 'compatible',
+    options?: PublishOptions,
+  ): SuiTransactionBlockResponse {
+    return JSON.parse(
+      withPublishDiagnostics(() =>
+        this.packageApi().upgrade(
+          packageId,
+          upgradeCapId,
+          modules,
+          dependencies,
+          policy,
+          options ? JSON.stringify(options) : null,
+        ),
+      ),
+    )
+  }
+
+  getPackageVersions(packageId: string): Record<string, string> {
+    return JSON.parse(this.packageApi().getVersions(packageId))
+  }
+
+  registerPackage(name: string, packageId: string) {
+    this.packageApi().register(name, packageId)
+  }
+
+  resolvePackage(name: string): ResolvedPackage {
+    return JSON.parse(this.packageApi().resolve(name))
+  }
+
+  expandType(type: string): string {
+    return this.packageApi().expandType(type)
   }
 
   getSuiBalance(address: string) {
@@ -133,10 +487,59 @@ export class SandboxClient {
     this.behaviourApi().enableSignatureChecks()
   }
 
+  // records the Move call stack of aborted transactions in `MoveAbort.stack`
+  enableAbortStackTraces() {
+    this.behaviourApi().enableAbortStackTraces()
+  }
+
+  disableAbortStackTraces() {
+    this.behaviourApi().disableAbortStackTraces()
+  }
+
   getNormalizedFunction(params: GetNormalizedMoveFunctionParams) {
     return JSON.parse(this.packageApi().getNormalizedMoveFunction(params.package, params.module, params.function))
   }
 
+  createObject(input: CreateObjectParams): string {
+    return this.objectApi().createObject(JSON.stringify(input))
+  }
+
+  patchFields(objectId: string, fields: Record<string, unknown>): number {
+    return this.objectApi().patchFields(objectId, JSON.stringify(fields))
+  }
+
+  transferObject(objectId: string, recipient: string): number {
+    return this.objectApi().transferObject(objectId, recipient)
+  }
+
+  shareObject(objectId: string): number {
+    return this.objectApi().shareObject(objectId)
+  }
+
+  freezeObject(objectId: string): number {
+    return this.objectApi().freezeObject(objectId)
+  }
+
+  addDynamicObjectField(parentId: string, objectId: string): string {
+    return this.objectApi().addDynamicObjectField(parentId, objectId)
+  }
+
+  getNormalizedMoveModulesByPackage(params: GetNormalizedMoveModulesByPackageParams): SuiMoveNormalizedModules {
+    return JSON.parse(this.packageApi().getNormalizedMoveModulesByPackage(params.package))
+  }
+
+  getNormalizedMoveModule(params: GetNormalizedMoveModuleParams): SuiMoveNormalizedModule {
+    return JSON.parse(this.packageApi().getNormalizedMoveModule(params.package, params.module))
+  }
+
+  getNormalizedMoveStruct(params: GetNormalizedMoveStructParams): SuiMoveNormalizedStruct {
+    return JSON.parse(this.packageApi().getNormalizedMoveStruct(params.package, params.module, params.struct))
+  }
+
+  getMoveFunctionArgTypes(params: GetMoveFunctionArgTypesParams): SuiMoveFunctionArgType[] {
+    return JSON.parse(this.packageApi().getMoveFunctionArgTypes(params.package, params.module, params.function))
+  }
+
   tryGetPastObject(input: TryGetPastObjectParams): ObjectRead {
     return JSON.parse(this.objectApi().getPast(JSON.stringify(input)))
   }
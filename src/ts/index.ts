@@ -2,3 +2,4 @@ export * from '../../index'
 export * from './client'
 export * from './proxy'
 export * from './package'
+export * from './snapshot'
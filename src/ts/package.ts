@@ -1,5 +1,8 @@
 import { execSync } from 'child_process'
-import { SandboxClient } from './client'
+import { existsSync, readdirSync, readFileSync } from 'fs'
+import { basename, join } from 'path'
+import { Signer } from '@mysten/sui/cryptography'
+import { PublishOptions, SandboxClient, UpgradePolicy } from './client'
 
 interface BuildOutput {
   modules: string[]
@@ -7,12 +10,61 @@ interface BuildOutput {
   digest: string
 }
 
-export function publishPackage(sandbox: SandboxClient, movePackageSrcDir: string, owner: string) {
+export function publishPackage(
+  sandbox: SandboxClient,
+  movePackageSrcDir: string,
+  owner: string,
+  options?: PublishOptions,
+) {
   const buildOutput = compileMovePackage(movePackageSrcDir)
   const moduleBytes = extractModuleBytes(buildOutput)
   const dependencyIds = extractDependencyIds(buildOutput)
 
-  return sandbox.publishPackage(moduleBytes, dependencyIds, owner)
+  return sandbox.publishPackage(moduleBytes, dependencyIds, owner, {
+    ...readPackageSources(movePackageSrcDir),
+    ...options,
+  })
+}
+
+export function verifyPackage(sandbox: SandboxClient, movePackageSrcDir: string, dependencyIds?: string[]) {
+  const buildOutput = compileMovePackage(movePackageSrcDir)
+  const moduleBytes = extractModuleBytes(buildOutput)
+
+  return sandbox.verifyPackage(moduleBytes, dependencyIds ?? extractDependencyIds(buildOutput))
+}
+
+export async function publishPackageSigned(
+  sandbox: SandboxClient,
+  movePackageSrcDir: string,
+  signer: Signer,
+  options?: PublishOptions,
+) {
+  const buildOutput = compileMovePackage(movePackageSrcDir)
+  const moduleBytes = extractModuleBytes(buildOutput)
+  const dependencyIds = extractDependencyIds(buildOutput)
+
+  const transactionBlock = sandbox.buildPublishTransaction(moduleBytes, dependencyIds, signer.toSuiAddress(), options)
+  const { signature } = await signer.signTransaction(Buffer.from(transactionBlock, 'base64'))
+
+  return sandbox.executeTransactionBlock({ transactionBlock, signature })
+}
+
+export function upgradePackage(
+  sandbox: SandboxClient,
+  movePackageSrcDir: string,
+  packageId: string,
+  upgradeCapId: string,
+  policy: UpgradePolicy = 'compatible',
+  options?: PublishOptions,
+) {
+  const buildOutput = compileMovePackage(movePackageSrcDir)
+  const moduleBytes = extractModuleBytes(buildOutput)
+  const dependencyIds = extractDependencyIds(buildOutput)
+
+  return sandbox.upgradePackage(packageId, upgradeCapId, moduleBytes, dependencyIds, policy, {
+    ...readPackageSources(movePackageSrcDir),
+    ...options,
+  })
 }
 
 function compileMovePackage(packageDir: string): BuildOutput {
@@ -31,6 +83,33 @@ function compileMovePackage(packageDir: string): BuildOutput {
   }
 }
 
+// source maps written by `sui move build` for the root package, plus the sources they refer to
+function readPackageSources(packageDir: string): Pick<PublishOptions, 'sourceMaps' | 'sources'> {
+  const buildDir = join(packageDir, 'build')
+  if (!existsSync(buildDir)) {
+    return {}
+  }
+
+  const sourceMaps = readdirSync(buildDir)
+    .map((name) => join(buildDir, name, 'source_maps'))
+    .filter((dir) => existsSync(dir))
+    .flatMap((dir) =>
+      readdirSync(dir)
+        .filter((file) => file.endsWith('.mvsm'))
+        .map((file) => ({
+          module: basename(file, '.mvsm'),
+          sourceMap: readFileSync(join(dir, file)).toString('base64'),
+        })),
+    )
+
+  const sourcesDir = join(packageDir, 'sources')
+  const sources = readdirSync(sourcesDir, { recursive: true, encoding: 'utf8' })
+    .filter((file) => file.endsWith('.move'))
+    .map((file) => ({ path: join(sourcesDir, file), contents: readFileSync(join(sourcesDir, file), 'utf8') }))
+
+  return { sourceMaps, sources }
+}
+
 function parseBuildOutput(output: string): BuildOutput {
   const jsonMatch = output.match(/\{[\s\S]*\}/)
   if (!jsonMatch) {
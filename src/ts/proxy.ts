@@ -8,26 +8,36 @@ import {
   GetDynamicFieldObjectParams,
   GetDynamicFieldsParams,
   GetLatestCheckpointSequenceNumberParams,
+  GetMoveFunctionArgTypesParams,
   GetNormalizedMoveFunctionParams,
+  GetNormalizedMoveModuleParams,
+  GetNormalizedMoveModulesByPackageParams,
+  GetNormalizedMoveStructParams,
+  GetProtocolConfigParams,
   GetReferenceGasPriceParams,
   GetTransactionBlockParams,
   MultiGetObjectsParams,
   ObjectRead,
   PaginatedCoins,
   PaginatedTransactionResponse,
+  ProtocolConfig,
   QueryTransactionBlocksParams,
   SuiClient,
+  SuiMoveFunctionArgType,
   SuiMoveNormalizedFunction,
+  SuiMoveNormalizedModule,
+  SuiMoveNormalizedModules,
+  SuiMoveNormalizedStruct,
   SuiObjectResponse,
   SuiTransactionBlockResponse,
   TryGetPastObjectParams,
 } from '@mysten/sui/client'
-import { SandboxClient } from './client'
+import { SandboxClient, SandboxOptions } from './client'
 import { Signer } from '@mysten/sui/cryptography'
 import { Transaction } from '@mysten/sui/transactions'
 
-export function createSandboxClient(): { client: SuiClient; sandbox: SandboxClient } {
-  const sandbox = new SandboxClient()
+export function createSandboxClient(options?: SandboxOptions): { client: SuiClient; sandbox: SandboxClient } {
+  const sandbox = new SandboxClient(options)
 
   const client = new Proxy({} as SuiClient, {
     get(_, prop) {
@@ -66,7 +76,7 @@ export function createSandboxClient(): { client: SuiClient; sandbox: SandboxClie
         },
 
         async multiGetObjects(input: MultiGetObjectsParams): Promise<SuiObjectResponse[]> {
-          const objects = Promise.all(input.ids.map((id) => this.getObject!({ id })))
+          const objects = Promise.all(input.ids.map((id) => this.getObject!({ id, options: input.options })))
 
           return objects
         },
@@ -88,6 +98,12 @@ export function createSandboxClient(): { client: SuiClient; sandbox: SandboxClie
             transactionBytes = transaction
           } else {
             transaction.setSenderIfNotSet(signer.toSuiAddress())
+
+            // the sandbox pays for gas itself, so no coins of the sender are selected
+            if (sandbox.getGasMode() !== 'metered' && !transaction.getData().gasData.payment) {
+              transaction.setGasPayment([])
+            }
+
             transactionBytes = await transaction.build({ client: this as SuiClient })
           }
 
@@ -114,10 +130,32 @@ export function createSandboxClient(): { client: SuiClient; sandbox: SandboxClie
           return sandbox.getNormalizedFunction(params)
         },
 
+        async getNormalizedMoveModulesByPackage(
+          params: GetNormalizedMoveModulesByPackageParams,
+        ): Promise<SuiMoveNormalizedModules> {
+          return sandbox.getNormalizedMoveModulesByPackage(params)
+        },
+
+        async getNormalizedMoveModule(params: GetNormalizedMoveModuleParams): Promise<SuiMoveNormalizedModule> {
+          return sandbox.getNormalizedMoveModule(params)
+        },
+
+        async getNormalizedMoveStruct(params: GetNormalizedMoveStructParams): Promise<SuiMoveNormalizedStruct> {
+          return sandbox.getNormalizedMoveStruct(params)
+        },
+
+        async getMoveFunctionArgTypes(params: GetMoveFunctionArgTypesParams): Promise<SuiMoveFunctionArgType[]> {
+          return sandbox.getMoveFunctionArgTypes(params)
+        },
+
         async getReferenceGasPrice(_: GetReferenceGasPriceParams = {}): Promise<bigint> {
           return BigInt(sandbox.stateApi().getReferenceGasPrice())
         },
 
+        async getProtocolConfig(_: GetProtocolConfigParams = {}): Promise<ProtocolConfig> {
+          return JSON.parse(sandbox.stateApi().getProtocolConfig())
+        },
+
         async getCoins(params: GetCoinsParams): Promise<PaginatedCoins> {
           const coins = sandbox.getCoins(params.owner, params.coinType)
 
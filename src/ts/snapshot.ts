@@ -0,0 +1,140 @@
+import { existsSync, readFileSync, writeFileSync } from 'fs'
+import { GasCostSummary, SuiTransactionBlockResponse } from '@mysten/sui/client'
+
+export interface GasSnapshotOptions {
+  // relative increase of the total gas allowed before `check` fails, `0.05` allows 5%
+  threshold?: number
+  // overwrite the snapshot file with the recorded values instead of comparing against it
+  update?: boolean
+}
+
+export interface GasSnapshotEntry {
+  computationCost: number
+  storageCost: number
+  storageRebate: number
+  total: number
+}
+
+export interface GasSnapshotDelta {
+  label: string
+  previous: number | null
+  current: number
+  delta: number
+  // relative change of the total gas, `null` for labels missing from the snapshot file
+  change: number | null
+}
+
+export class GasSnapshotError extends Error {
+  constructor(
+    public readonly deltas: GasSnapshotDelta[],
+    threshold: number,
+  ) {
+    super(
+      `Gas usage increased by more than ${threshold * 100}%:\n` +
+        deltas.map((delta) => `  ${delta.label}: ${delta.previous} -> ${delta.current} (+${delta.delta})`).join('\n'),
+    )
+    this.name = 'GasSnapshotError'
+  }
+}
+
+// records the gas used by labeled transactions and compares it with the values stored in a
+// snapshot file by a previous run, like `forge snapshot`
+export class GasSnapshot {
+  private readonly entries = new Map<string, GasSnapshotEntry>()
+  private readonly threshold: number
+  private readonly update: boolean
+
+  constructor(
+    private readonly path: string,
+    options?: GasSnapshotOptions,
+  ) {
+    this.threshold = options?.threshold ?? 0
+    this.update = options?.update ?? process.env.GAS_SNAPSHOT_UPDATE === '1'
+  }
+
+  record(label: string, response: SuiTransactionBlockResponse) {
+    if (!response.effects) {
+      throw new Error(`Transaction ${response.digest} has no effects, request them with \`showEffects\``)
+    }
+
+    this.entries.set(label, gasEntry(response.effects.gasUsed))
+
+    return response
+  }
+
+  previous(): Record<string, GasSnapshotEntry> {
+    return existsSync(this.path) ? JSON.parse(readFileSync(this.path, 'utf8')) : {}
+  }
+
+  deltas(): GasSnapshotDelta[] {
+    const previous = this.previous()
+
+    return [...this.entries.entries()].map(([label, entry]) => {
+      const before = previous[label]?.total ?? null
+
+      return {
+        label,
+        previous: before,
+        current: entry.total,
+        delta: entry.total - (before ?? 0),
+        change: before === null ? null : relativeChange(before, entry.total),
+      }
+    })
+  }
+
+  // writes the snapshot file on the first run or in update mode, otherwise fails on regressions
+  // and adds the labels the snapshot file doesn't have yet
+  check() {
+    const deltas = this.deltas()
+
+    if (this.update || !existsSync(this.path)) {
+      this.save()
+      return deltas
+    }
+
+    const regressions = deltas.filter((delta) => delta.change !== null && delta.change > this.threshold)
+
+    if (regressions.length > 0) {
+      throw new GasSnapshotError(regressions, this.threshold)
+    }
+
+    const added = deltas.filter((delta) => delta.previous === null).map((delta) => delta.label)
+
+    if (added.length > 0) {
+      this.save(added)
+    }
+
+    return deltas
+  }
+
+  // only the given labels are written when set, the others keep their stored values
+  save(labels?: string[]) {
+    const recorded = [...this.entries.entries()].filter(([label]) => !labels || labels.includes(label))
+    const entries = { ...this.previous(), ...Object.fromEntries(recorded) }
+    const sorted = Object.fromEntries(Object.entries(entries).sort(([a], [b]) => a.localeCompare(b)))
+
+    writeFileSync(this.path, JSON.stringify(sorted, null, 2) + '\n')
+  }
+}
+
+function gasEntry(gasUsed: GasCostSummary): GasSnapshotEntry {
+  const computationCost = Number(gasUsed.computationCost)
+  const storageCost = Number(gasUsed.storageCost)
+  const storageRebate = Number(gasUsed.storageRebate)
+
+  return {
+    computationCost,
+    storageCost,
+    storageRebate,
+    total: computationCost + storageCost - storageRebate,
+  }
+}
+
+// storage rebates can make the total negative, the change is relative to its magnitude
+function relativeChange(before: number, after: number) {
+  if (before === 0) {
+    return after > 0 ? Infinity : 0
+  }
+
+  return (after - before) / Math.abs(before)
+}
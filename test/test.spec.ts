@@ -1,15 +1,31 @@
-import { createSandboxClient, publishPackage, SandboxClient } from '../src/ts/index'
+import {
+  createSandboxClient,
+  formatMoveStackTrace,
+  GasSnapshot,
+  GasSnapshotError,
+  publishPackage,
+  publishPackageSigned,
+  PublishError,
+  SandboxClient,
+  upgradePackage,
+  UpgradePolicy,
+  verifyPackage,
+} from '../src/ts/index'
 import { Secp256k1Keypair } from '@mysten/sui/keypairs/secp256k1'
 import { Transaction } from '@mysten/sui/transactions'
 import { MIST_PER_SUI, SUI_CLOCK_OBJECT_ID } from '@mysten/sui/utils'
 import { AdminClient } from './adminClient'
 import { SharedClient } from './SharedClient'
 import { ClockClient } from './ClockClient'
-import { SuiTransactionBlockResponse } from '@mysten/sui/client'
+import { SuiClient, SuiTransactionBlockResponse } from '@mysten/sui/client'
 import { DynamicClient } from './DynamicClient'
+import { mkdtempSync, readFileSync, writeFileSync } from 'fs'
+import { tmpdir } from 'os'
+import { join } from 'path'
+import { gzipSync } from 'zlib'
 
 const INITIAL_BALANCE = 1000000000000000n
-const GAS_BUDGET = 1000000000000000n
+const GAS_BUDGET = 10n * MIST_PER_SUI
 const GAS_PRICE = 100
 
 describe('SuiSandboxClient', () => {
@@ -45,6 +61,16 @@ describe('SuiSandboxClient', () => {
     return publishTestPackage('./move-fixtures/dynamic_fields')
   }
 
+  const publishDisplayPackage = () => {
+    return publishTestPackage('./move-fixtures/display')
+  }
+
+  const publishUpgradeablePackage = () => {
+    const published = publishSharedPackage()
+
+    return { ...published, upgradeCapId: findUpgradeCapId(published.publishResult) }
+  }
+
   const publishAdminPackage = () => {
     const { client, sandbox, packageId, sender, publishResult } = publishTestPackage('./move-fixtures/admin')
 
@@ -73,6 +99,121 @@ describe('SuiSandboxClient', () => {
       newSandbox.storageApi().restoreFromSnapshot(snapshot)
       expect(newSandbox.getBalance(address)).toBe(10000)
     })
+
+    it('keeps debug info out of snapshots unless requested', () => {
+      const { sandbox, packageId } = publishTestPackage('./move-fixtures/pool')
+
+      const snapshot = sandbox.storageApi().takeSnapshot()
+      const withDebugInfo = sandbox.storageApi().takeSnapshot(true)
+      expect(withDebugInfo.length).toBeGreaterThan(snapshot.length)
+
+      const newSandbox = new SandboxClient()
+      newSandbox.storageApi().restoreFromSnapshot(withDebugInfo)
+      expect(newSandbox.getObject({ id: packageId }).data.objectId).toBe(packageId)
+    })
+
+    it('restores snapshots taken before the format was versioned', () => {
+      // 5 empty storage maps, 12 empty transaction indices and checkpoint 7
+      const legacy = gzipSync(Buffer.from([...new Array(17).fill(0), 7, 0, 0, 0, 0, 0, 0, 0]))
+      const sandbox = new SandboxClient()
+
+      sandbox.storageApi().restoreFromSnapshot([...legacy])
+      expect(sandbox.stateApi().getLatestCheckpoint()).toBe(7)
+
+      expect(() => sandbox.storageApi().restoreFromSnapshot([...gzipSync(Buffer.from('SUISANDBOX\x09'))])).toThrow(
+        'Unsupported snapshot version 9',
+      )
+    })
+  })
+
+  describe('Gas modes', () => {
+    const newPool = (packageId: string) => {
+      const tx = new Transaction()
+      tx.moveCall({ target: `${packageId}::pool::new` })
+
+      return tx
+    }
+
+    it('executes without gas coins when unmetered', async () => {
+      const { client, sandbox, packageId } = publishTestPackage('./move-fixtures/pool')
+      sandbox.setGasMode('unmetered')
+      const poor = Secp256k1Keypair.generate()
+
+      const result = checkTxSuccedded(
+        await client.signAndExecuteTransaction({ transaction: newPool(packageId), signer: poor }),
+      )
+
+      expect(result.effects!.gasUsed).toMatchObject({ computationCost: '0', storageCost: '0' })
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBe(0)
+    })
+
+    it('funds the gas owner from the faucet', async () => {
+      const { client, sandbox, packageId } = publishTestPackage('./move-fixtures/pool')
+      sandbox.setGasMode('metered-but-sponsored-by-faucet')
+      const poor = Secp256k1Keypair.generate()
+
+      const tx = newPool(packageId)
+      tx.setGasBudget(Number(MIST_PER_SUI))
+      const result = checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: poor }))
+
+      expect(Number(result.effects!.gasUsed.computationCost)).toBeGreaterThan(0)
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBeLessThan(Number(MIST_PER_SUI))
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBeGreaterThan(0)
+    })
+
+    it('does not fund transactions rejected by validation', async () => {
+      const { client, sandbox, packageId } = publishTestPackage('./move-fixtures/pool')
+      sandbox.setGasMode('metered-but-sponsored-by-faucet')
+      const poor = Secp256k1Keypair.generate()
+
+      const tx = newPool(packageId)
+      tx.setGasBudget(1)
+      const { bytes, signature } = await tx.sign({ client, signer: poor })
+
+      checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBe(0)
+    })
+
+    it('publishes without gas coins when the gas mode does not charge', () => {
+      const { sandbox } = createSandboxClient()
+      const poor = Secp256k1Keypair.generate()
+
+      sandbox.setGasMode('unmetered')
+      checkTxSuccedded(publishPackage(sandbox, './move-fixtures/pool', poor.toSuiAddress()))
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBe(0)
+
+      sandbox.setGasMode('metered-but-sponsored-by-faucet')
+      checkTxSuccedded(publishPackage(sandbox, './move-fixtures/pool', poor.toSuiAddress(), { autoFund: false }))
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBeGreaterThan(0)
+      expect(sandbox.getBalance(poor.toSuiAddress())).toBeLessThan(10_000_000_000)
+    })
+
+    it('rejects unknown gas modes', () => {
+      const sandbox = new SandboxClient({ gasMode: 'unmetered' })
+      expect(sandbox.getGasMode()).toBe('unmetered')
+      expect(() => sandbox.setGasMode('free' as any)).toThrow('Invalid gas mode')
+    })
+  })
+
+  describe('Sandbox options', () => {
+    it('runs on the requested protocol version', async () => {
+      const { client: defaultClient } = createSandboxClient()
+      const { maxSupportedProtocolVersion } = await defaultClient.getProtocolConfig()
+      const version = Number(maxSupportedProtocolVersion) - 1
+
+      const { client, sandbox } = createSandboxClient({ protocolVersion: version })
+      expect((await client.getProtocolConfig()).protocolVersion).toBe(String(version))
+
+      const sender = Secp256k1Keypair.generate()
+      checkTxSuccedded(publishPackage(sandbox, './move-fixtures/shared', sender.toSuiAddress()))
+    })
+
+    it('rejects unsupported protocol versions and unknown framework packages', () => {
+      expect(() => new SandboxClient({ protocolVersion: 1_000_000 })).toThrow('is not supported')
+      expect(() => new SandboxClient({ frameworkPackages: [{ id: '0x42', modules: [] }] })).toThrow(
+        'is not a framework package',
+      )
+    })
   })
 
   describe('balance operations', () => {
@@ -96,7 +237,7 @@ describe('SuiSandboxClient', () => {
 
       sandbox.advanceClockByMillis(100)
 
-      const tx = createTransferTransaction(sender, recipient, coinIds)
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
       const transactionData = await tx.build({ client })
       const { signature } = await sender.signTransaction(transactionData)
 
@@ -116,7 +257,7 @@ describe('SuiSandboxClient', () => {
 
       sandbox.advanceClockByMillis(100)
 
-      const tx = createTransferTransaction(sender, recipient, coinIds)
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
       checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: sender }))
 
       expect(sandbox.getBalance(recipient.toSuiAddress())).toBe(Number(INITIAL_BALANCE) * 2)
@@ -125,7 +266,7 @@ describe('SuiSandboxClient', () => {
     it('rejects transaction with invalid signer', async () => {
       const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
 
-      const tx = createTransferTransaction(sender, recipient, coinIds)
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
       checkTxFailed(
         await client.signAndExecuteTransaction({
           transaction: tx,
@@ -141,7 +282,7 @@ describe('SuiSandboxClient', () => {
 
       sandbox.disableSigChecks()
 
-      const tx = createTransferTransaction(sender, recipient, coinIds)
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
       checkTxSuccedded(
         await client.signAndExecuteTransaction({
           transaction: tx,
@@ -157,7 +298,7 @@ describe('SuiSandboxClient', () => {
 
       sandbox.advanceClockByMillis(100)
 
-      const tx = createTransferTransaction(sender, recipient, coinIds)
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
       sandbox.behaviourApi().setRejectNextTransaction('i dont like you')
       const response = await client.signAndExecuteTransaction({ transaction: tx, signer: sender })
 
@@ -182,9 +323,9 @@ describe('SuiSandboxClient', () => {
 
   describe('transaction status', () => {
     it('retrieves status for successful and failed transactions', async () => {
-      const { client, sender, recipient, coinIds } = setupTransferTest()
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
 
-      const tx = createTransferTransaction(sender, recipient, coinIds)
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
 
       const successfulResponse = await client.signAndExecuteTransaction({
         transaction: tx,
@@ -209,6 +350,555 @@ describe('SuiSandboxClient', () => {
     })
   })
 
+  describe('Input validation', () => {
+    const rejectionOf = async (
+      sandbox: SandboxClient,
+      client: SuiClient,
+      sender: Secp256k1Keypair,
+      tx: Transaction,
+    ) => {
+      const { bytes, signature } = await tx.sign({ client, signer: sender })
+
+      return checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature })).errors!.join()
+    }
+
+    it('rejects stale versions and mismatching digests', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+      const staleRef = objectRef(sandbox, coinIds[0])
+
+      checkTxSuccedded(
+        await client.signAndExecuteTransaction({
+          transaction: createTransferTransaction(sandbox, sender, recipient, coinIds),
+          signer: sender,
+        }),
+      )
+
+      const stale = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      stale.setGasPayment([staleRef])
+      expect(await rejectionOf(sandbox, client, sender, stale)).toContain('not available for consumption')
+
+      const wrongDigest = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      wrongDigest.setGasPayment([{ ...objectRef(sandbox, coinIds[0]), digest: staleRef.digest }])
+      expect(await rejectionOf(sandbox, client, sender, wrongDigest)).toMatch(/digest/i)
+    })
+
+    it('rejects gas budgets and prices outside the protocol bounds', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+      const maxTxGas = (await client.getProtocolConfig()).attributes['max_tx_gas']
+
+      const tooExpensive = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      tooExpensive.setGasBudget(Number(maxTxGas?.u64) + 1)
+      expect(await rejectionOf(sandbox, client, sender, tooExpensive)).toMatch(/gas budget/i)
+
+      const tooCheap = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      tooCheap.setGasBudget(1)
+      expect(await rejectionOf(sandbox, client, sender, tooCheap)).toMatch(/gas budget/i)
+
+      const underpriced = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      underpriced.setGasPrice(1)
+      expect(await rejectionOf(sandbox, client, sender, underpriced)).toMatch(/reference gas price/i)
+    })
+
+    it('rejects oversized pure arguments', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+      const maxPureArgumentSize = (await client.getProtocolConfig()).attributes['max_pure_argument_size']
+
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      tx.pure.vector('u8', new Array(Number(maxPureArgumentSize?.u32) + 1).fill(0))
+      expect(await rejectionOf(sandbox, client, sender, tx)).toContain('maximum pure argument size')
+    })
+  })
+
+  describe('Owned object locks', () => {
+    it('rejects a second transaction using versions locked by another one', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const transfer = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      const first = await transfer.sign({ client, signer: sender })
+
+      const conflicting = new Transaction()
+      conflicting.transferObjects([coinIds[1]], sender.toSuiAddress())
+      conflicting.setSender(sender.toSuiAddress())
+      conflicting.setGasBudget(Number(GAS_BUDGET))
+      conflicting.setGasPrice(GAS_PRICE)
+      conflicting.setGasPayment([objectRef(sandbox, coinIds[0])])
+      const second = await conflicting.sign({ client, signer: sender })
+
+      const digest = sandbox.lockTransactionBlock({ transactionBlock: first.bytes, signature: first.signature })
+
+      const equivocation = checkTxFailed(
+        sandbox.executeTransactionBlock({ transactionBlock: second.bytes, signature: second.signature }),
+      )
+      expect(equivocation.errors!.join()).toContain(digest)
+      expect(() =>
+        sandbox.lockTransactionBlock({ transactionBlock: second.bytes, signature: second.signature }),
+      ).toThrow('locked')
+
+      const executed = checkTxSuccedded(
+        sandbox.executeTransactionBlock({ transactionBlock: first.bytes, signature: first.signature }),
+      )
+      expect(executed.digest).toBe(digest)
+
+      const stale = checkTxFailed(
+        sandbox.executeTransactionBlock({ transactionBlock: second.bytes, signature: second.signature }),
+      )
+      expect(stale.errors!.join()).toContain('not available for consumption')
+    })
+
+    it('does not lock objects on dry runs', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      const bytes = await tx.build({ client })
+      await client.dryRunTransactionBlock({ transactionBlock: bytes })
+
+      const other = createTransferTransaction(sandbox, sender, sender, coinIds)
+      checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: other, signer: sender }))
+    })
+
+    it('leaves a pending rejection to the execution of a locked transaction', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      const { bytes, signature } = await tx.sign({ client, signer: sender })
+
+      sandbox.rejectNextTransaction('not yet')
+      sandbox.lockTransactionBlock({ transactionBlock: bytes, signature })
+
+      const rejected = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+      expect(rejected.errors!.join()).toContain('not yet')
+
+      // the rejected execution keeps the locks taken before it
+      const other = await createTransferTransaction(sandbox, sender, sender, coinIds).sign({ client, signer: sender })
+      expect(() =>
+        sandbox.lockTransactionBlock({ transactionBlock: other.bytes, signature: other.signature }),
+      ).toThrow('locked')
+
+      checkTxSuccedded(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+    })
+
+    it('releases the locks a rejected execution took itself', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      const { bytes, signature } = await tx.sign({ client, signer: sender })
+
+      sandbox.rejectNextTransaction('not yet')
+      checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      const other = createTransferTransaction(sandbox, sender, sender, coinIds)
+      checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: other, signer: sender }))
+    })
+
+    it('drops locks when a snapshot is restored', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+      const snapshot = sandbox.storageApi().takeSnapshot()
+
+      const tx = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      const { bytes, signature } = await tx.sign({ client, signer: sender })
+      sandbox.lockTransactionBlock({ transactionBlock: bytes, signature })
+      sandbox.storageApi().restoreFromSnapshot(snapshot)
+
+      const other = createTransferTransaction(sandbox, sender, sender, coinIds)
+      checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: other, signer: sender }))
+    })
+  })
+
+  describe('Shared object congestion', () => {
+    it('cancels transactions over the per object budget', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+
+      sandbox.setCongestionControl({ mode: 'txCount', perObjectBudget: 1 })
+
+      checkTxSuccedded(await sharedClient.setValue(shared, 1))
+      const cancelled = checkTxFailed(await sharedClient.setValue(shared, 2))
+
+      expect(cancelled.errors!.join('\n')).toMatch(/Congestion/)
+      expect(await sharedClient.readValue(shared)).toBe(1)
+
+      sandbox.behaviourApi().bumpCheckpoint()
+      checkTxSuccedded(await sharedClient.setValue(shared, 3))
+    })
+
+    it('defers transactions to the next checkpoint', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+
+      sandbox.setCongestionControl({ mode: 'txCount', perObjectBudget: 1, maxDeferralCheckpoints: 1 })
+
+      checkTxSuccedded(await sharedClient.setValue(shared, 1))
+      checkTxSuccedded(await sharedClient.setValue(shared, 2))
+
+      expect(await sharedClient.readValue(shared)).toBe(2)
+    })
+
+    it('cancels transactions once sustained load defers them past the limit', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+
+      sandbox.setCongestionControl({ mode: 'txCount', perObjectBudget: 1, maxDeferralCheckpoints: 2 })
+
+      for (const value of [1, 2, 3]) {
+        checkTxSuccedded(await sharedClient.setValue(shared, value))
+      }
+      const cancelled = checkTxFailed(await sharedClient.setValue(shared, 4))
+
+      expect(cancelled.errors!.join('\n')).toMatch(/Congestion/)
+      expect(await sharedClient.readValue(shared)).toBe(3)
+
+      // every sealed checkpoint absorbs one budget of the deferred load
+      sandbox.behaviourApi().bumpCheckpoint()
+      checkTxSuccedded(await sharedClient.setValue(shared, 5))
+      checkTxFailed(await sharedClient.setValue(shared, 6))
+    })
+
+    it('admits a transaction over the budget when it is the first to use the object', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+
+      sandbox.setCongestionControl({ mode: 'gasBudget', perObjectBudget: 1 })
+
+      checkTxSuccedded(await sharedClient.setValue(shared, 1))
+      checkTxFailed(await sharedClient.setValue(shared, 2))
+      expect(await sharedClient.readValue(shared)).toBe(1)
+    })
+
+    it('stops limiting once disabled', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+
+      sandbox.setCongestionControl({ mode: 'txCount', perObjectBudget: 1 })
+      sandbox.setCongestionControl(null)
+
+      checkTxSuccedded(await sharedClient.setValue(shared, 1))
+      checkTxSuccedded(await sharedClient.setValue(shared, 2))
+    })
+  })
+
+  describe('Mempool', () => {
+    // every user races to overwrite the value of the shared object at `objects[index]` with their own index
+    const submitRace = async ({
+      objects = [0, 0, 0],
+      gasPrices = [],
+    }: { objects?: number[]; gasPrices?: number[] } = {}) => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const sharedObjects = [await sharedClient.new(), await sharedClient.new()]
+      const shared = sharedObjects[0]
+      const users = [sender, Secp256k1Keypair.generate(), Secp256k1Keypair.generate()]
+
+      for (const user of users.slice(1)) {
+        sandbox.mintSui(user.toSuiAddress(), Number(2n * GAS_BUDGET))
+      }
+
+      const digests: string[] = []
+
+      for (const [index, user] of users.entries()) {
+        const tx = new Transaction()
+        tx.moveCall({
+          target: `${packageId}::shared::set_value`,
+          arguments: [tx.object(sharedObjects[objects[index]]), tx.pure.u8(index)],
+        })
+        tx.setSender(user.toSuiAddress())
+        tx.setGasBudget(Number(GAS_BUDGET))
+        tx.setGasPrice(gasPrices[index] ?? GAS_PRICE)
+        const { bytes, signature } = await tx.sign({ client, signer: user })
+
+        digests.push(sandbox.submitTransactionBlock({ transactionBlock: bytes, signature }))
+      }
+
+      return { sandbox, sharedClient, shared, digests }
+    }
+
+    it('queues transactions until the batch is sealed', async () => {
+      const { sandbox, sharedClient, shared, digests } = await submitRace()
+
+      expect(sandbox.pendingTransactions()).toEqual(digests)
+      expect(await sharedClient.readValue(shared)).toBe(0)
+
+      const results = sandbox.sealBatch()
+
+      results.forEach((result) => checkTxSuccedded(result.response!))
+      expect(results.map((result) => result.digest)).toEqual(digests)
+      expect(await sharedClient.readValue(shared)).toBe(2)
+      expect(sandbox.pendingTransactions()).toEqual([])
+    })
+
+    it('lets later submissions front-run earlier ones when adversarial', async () => {
+      const { sandbox, sharedClient, shared, digests } = await submitRace()
+
+      const results = sandbox.sealBatch({ type: 'adversarial' })
+
+      expect(results.map((result) => result.digest)).toEqual([...digests].reverse())
+      expect(await sharedClient.readValue(shared)).toBe(0)
+    })
+
+    it('front-runs with the highest gas price first when adversarial', async () => {
+      const { sandbox, sharedClient, shared, digests } = await submitRace({ gasPrices: [2 * GAS_PRICE] })
+
+      const results = sandbox.sealBatch({ type: 'adversarial' })
+
+      expect(results.map((result) => digests.indexOf(result.digest))).toEqual([0, 2, 1])
+      expect(await sharedClient.readValue(shared)).toBe(1)
+    })
+
+    it('only reorders conflicting transactions when adversarial', async () => {
+      const { sandbox, digests } = await submitRace({ objects: [0, 0, 1] })
+
+      const results = sandbox.sealBatch({ type: 'adversarial' })
+
+      expect(results.map((result) => digests.indexOf(result.digest))).toEqual([1, 0, 2])
+    })
+
+    it('reports every transaction of the batch separately', async () => {
+      const { sandbox, sharedClient, shared, digests } = await submitRace()
+
+      sandbox.rejectNextTransaction('not in this batch')
+      const results = sandbox.sealBatch()
+
+      expect(results.map((result) => result.digest)).toEqual(digests)
+      expect(checkTxFailed(results[0].response!).errors!.join()).toContain('not in this batch')
+      results.slice(1).forEach((result) => checkTxSuccedded(result.response!))
+      expect(await sharedClient.readValue(shared)).toBe(2)
+    })
+
+    it('shuffles the same way for the same seed', async () => {
+      const order = async () => {
+        const { sandbox, digests } = await submitRace()
+
+        return sandbox.sealBatch({ type: 'random', seed: 42 }).map((result) => digests.indexOf(result.digest))
+      }
+
+      const first = await order()
+
+      expect(await order()).toEqual(first)
+      expect([...first].sort()).toEqual([0, 1, 2])
+    })
+
+    it('locks owned inputs on submission', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const transfer = createTransferTransaction(sandbox, sender, recipient, coinIds)
+      const submitted = await transfer.sign({ client, signer: sender })
+      sandbox.submitTransactionBlock({ transactionBlock: submitted.bytes, signature: submitted.signature })
+
+      const conflicting = await createTransferTransaction(sandbox, sender, sender, coinIds).sign({
+        client,
+        signer: sender,
+      })
+      checkTxFailed(
+        sandbox.executeTransactionBlock({ transactionBlock: conflicting.bytes, signature: conflicting.signature }),
+      )
+
+      checkTxSuccedded(sandbox.sealBatch()[0].response!)
+    })
+  })
+
+  describe('Gas coin smashing', () => {
+    const payWith = (
+      sandbox: SandboxClient,
+      sender: Secp256k1Keypair,
+      recipient: Secp256k1Keypair,
+      payment: string[],
+    ) => {
+      const tx = new Transaction()
+      const [coin] = tx.splitCoins(tx.gas, [tx.pure.u64(1000)])
+      tx.transferObjects([coin], recipient.toSuiAddress())
+      tx.setSender(sender.toSuiAddress())
+      tx.setGasBudget(Number(MIST_PER_SUI))
+      tx.setGasPrice(GAS_PRICE)
+      tx.setGasPayment(payment.map((objectId) => objectRef(sandbox, objectId)))
+
+      return tx
+    }
+
+    it('smashes all payment coins into the first one', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const result = checkTxSuccedded(
+        await client.signAndExecuteTransaction({
+          transaction: payWith(sandbox, sender, recipient, [coinIds[0], coinIds[1]]),
+          signer: sender,
+        }),
+      )
+
+      const { computationCost, storageCost, storageRebate } = result.effects!.gasUsed
+      const gasCost = Number(computationCost) + Number(storageCost) - Number(storageRebate)
+
+      expect(result.effects!.gasObject.reference.objectId).toBe(coinIds[0])
+      expect(result.objectChanges).toEqual(
+        expect.arrayContaining([
+          expect.objectContaining({ type: 'mutated', objectId: coinIds[0] }),
+          expect.objectContaining({ type: 'deleted', objectId: coinIds[1] }),
+        ]),
+      )
+      expect(result.balanceChanges).toHaveLength(2)
+      expect(result.balanceChanges).toEqual(
+        expect.arrayContaining([
+          expect.objectContaining({
+            owner: { AddressOwner: sender.toSuiAddress() },
+            amount: String(-1000 - gasCost),
+          }),
+          expect.objectContaining({
+            owner: { AddressOwner: recipient.toSuiAddress() },
+            amount: '1000',
+          }),
+        ]),
+      )
+      expect(sandbox.getBalance(sender.toSuiAddress())).toBe(Number(3n * INITIAL_BALANCE) - 1000 - gasCost)
+    })
+
+    it('rejects duplicate gas coins and gas coins used as inputs', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      const duplicate = await payWith(sandbox, sender, recipient, [coinIds[0], coinIds[0]]).sign({
+        client,
+        signer: sender,
+      })
+      const duplicateResult = checkTxFailed(
+        sandbox.executeTransactionBlock({ transactionBlock: duplicate.bytes, signature: duplicate.signature }),
+      )
+      expect(duplicateResult.errors!.join()).toContain('Duplicate')
+
+      const tx = payWith(sandbox, sender, recipient, [coinIds[0]])
+      tx.transferObjects([tx.object(coinIds[0])], recipient.toSuiAddress())
+      const asInput = await tx.sign({ client, signer: sender })
+      const asInputResult = checkTxFailed(
+        sandbox.executeTransactionBlock({ transactionBlock: asInput.bytes, signature: asInput.signature }),
+      )
+      expect(asInputResult.errors!.join()).toContain('Duplicate')
+    })
+
+    it('rejects more payment coins than the protocol allows', async () => {
+      const { client, sandbox, sender, recipient } = setupTransferTest()
+      const maxGasPaymentObjects = (await client.getProtocolConfig()).attributes['max_gas_payment_objects']
+
+      const coinIds = Array.from({ length: Number(maxGasPaymentObjects?.u32) + 1 }, () =>
+        sandbox.coinApi().mintSui(sender.toSuiAddress(), 1000),
+      )
+      const tx = payWith(sandbox, sender, recipient, coinIds)
+      const { bytes, signature } = await tx.sign({ client, signer: sender })
+
+      const result = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+      expect(result.errors!.join()).toContain('maximum number of gas payment objects')
+    })
+  })
+
+  describe('Sponsored transactions', () => {
+    const setupSponsoredTransfer = () => {
+      const { client, sandbox } = createSandboxClient()
+      const [sender, sponsor, recipient] = Array.from({ length: 3 }, () => Secp256k1Keypair.generate())
+
+      const coinId = sandbox.coinApi().mintSui(sender.toSuiAddress(), 1000)
+      sandbox.coinApi().mintSui(sponsor.toSuiAddress(), Number(10n * MIST_PER_SUI))
+
+      const tx = new Transaction()
+      tx.transferObjects([tx.object(coinId)], recipient.toSuiAddress())
+      tx.setSender(sender.toSuiAddress())
+      tx.setGasOwner(sponsor.toSuiAddress())
+      tx.setGasBudget(Number(MIST_PER_SUI))
+
+      return { client, sandbox, sender, sponsor, recipient, tx }
+    }
+
+    it('requires signatures of both the sender and the sponsor', async () => {
+      const { client, sandbox, sender, sponsor, recipient, tx } = setupSponsoredTransfer()
+      const bytes = await tx.build({ client })
+
+      const { signature: senderSignature } = await sender.signTransaction(bytes)
+      const { signature: sponsorSignature } = await sponsor.signTransaction(bytes)
+
+      const unsponsored = checkTxFailed(
+        sandbox.executeTransactionBlock({ transactionBlock: bytes, signature: senderSignature }),
+      )
+      expect(unsponsored.errors!.join()).toContain('signatures')
+
+      checkTxSuccedded(
+        sandbox.executeTransactionBlock({ transactionBlock: bytes, signature: [senderSignature, sponsorSignature] }),
+      )
+      expect(sandbox.getBalance(recipient.toSuiAddress())).toBe(1000)
+      expect(sandbox.getBalance(sender.toSuiAddress())).toBe(0)
+      expect(sandbox.getBalance(sponsor.toSuiAddress())).toBeLessThan(Number(10n * MIST_PER_SUI))
+    })
+
+    it('rejects gas coins not owned by the sponsor', async () => {
+      const { client, sandbox, sender, sponsor, tx } = setupSponsoredTransfer()
+      const senderGasId = sandbox.coinApi().mintSui(sender.toSuiAddress(), Number(MIST_PER_SUI))
+      tx.setGasPayment([objectRef(sandbox, senderGasId)])
+      tx.setGasPrice(GAS_PRICE)
+
+      const bytes = await tx.build({ client })
+      const signatures = await Promise.all(
+        [sender, sponsor].map(async (signer) => (await signer.signTransaction(bytes)).signature),
+      )
+
+      const result = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature: signatures }))
+      expect(result.errors!.join()).toContain(`is owned by account address ${sender.toSuiAddress()}`)
+    })
+  })
+
+  describe('Gas snapshots', () => {
+    it('writes a snapshot and fails when gas grows past the threshold', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+      const path = join(mkdtempSync(join(tmpdir(), 'gas-snapshot-')), 'gas-snapshot.json')
+
+      const snapshot = new GasSnapshot(path, { threshold: 0.1 })
+      const response = await client.signAndExecuteTransaction({
+        transaction: createTransferTransaction(sandbox, sender, recipient, coinIds),
+        signer: sender,
+        options: { showEffects: true },
+      })
+      snapshot.record('transfer', checkTxSuccedded(response))
+
+      expect(snapshot.check()).toEqual([expect.objectContaining({ label: 'transfer', previous: null })])
+      const written = JSON.parse(readFileSync(path, 'utf8'))
+      expect(written.transfer.total).toBeGreaterThan(0)
+
+      expect(snapshot.check()).toEqual([expect.objectContaining({ label: 'transfer', delta: 0, change: 0 })])
+
+      written.transfer.total = Math.floor(written.transfer.total / 2)
+      writeFileSync(path, JSON.stringify(written))
+      expect(() => snapshot.check()).toThrow(GasSnapshotError)
+
+      new GasSnapshot(path, { update: true }).record('transfer', response).check()
+      expect(snapshot.check()[0].delta).toBe(0)
+    })
+
+    it('adds new labels to an existing snapshot without touching stored ones', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+      const path = join(mkdtempSync(join(tmpdir(), 'gas-snapshot-')), 'gas-snapshot.json')
+      const stored = { computationCost: 0, storageCost: 0, storageRebate: 100, total: -100 }
+      writeFileSync(path, JSON.stringify({ stored }))
+
+      const response = await client.signAndExecuteTransaction({
+        transaction: createTransferTransaction(sandbox, sender, recipient, coinIds),
+        signer: sender,
+        options: { showEffects: true },
+      })
+      const snapshot = new GasSnapshot(path).record('transfer', checkTxSuccedded(response))
+      snapshot.record('stored', { ...response, effects: { ...response.effects!, gasUsed: gasUsed(-50) } })
+
+      expect(() => snapshot.check()).toThrow(GasSnapshotError)
+      expect(Object.keys(JSON.parse(readFileSync(path, 'utf8')))).toEqual(['stored'])
+
+      snapshot.record('stored', { ...response, effects: { ...response.effects!, gasUsed: gasUsed(-150) } })
+      expect(snapshot.check()).toEqual([
+        expect.objectContaining({ label: 'transfer', previous: null }),
+        expect.objectContaining({ label: 'stored', previous: -100, change: -0.5 }),
+      ])
+
+      const written = JSON.parse(readFileSync(path, 'utf8'))
+      expect(written.stored).toEqual(stored)
+      expect(written.transfer.total).toBeGreaterThan(0)
+    })
+  })
+
   describe('Admin package', () => {
     it('publishes package successfully', () => {
       const { publishResult } = publishAdminPackage()
@@ -278,6 +968,293 @@ describe('SuiSandboxClient', () => {
     })
   })
 
+  describe('Publish options', () => {
+    it('publishes immutable package without UpgradeCap', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const result = checkTxSuccedded(
+        publishPackage(sandbox, './move-fixtures/shared', sender.toSuiAddress(), { upgradeable: false }),
+      )
+
+      expect(findUpgradeCapId(result)).toBe('')
+    })
+
+    it('fails without auto funding when sender has no gas', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      expect(() =>
+        publishPackage(sandbox, './move-fixtures/shared', sender.toSuiAddress(), { autoFund: false }),
+      ).toThrow('no SUI coins')
+    })
+
+    it('fails when gas budget is too low', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      checkTxFailed(publishPackage(sandbox, './move-fixtures/shared', sender.toSuiAddress(), { gasBudget: 1000 }))
+    })
+
+    it('verifies publisher signature in signed mode', async () => {
+      const { sandbox } = createSandboxClient()
+      const publisher = Secp256k1Keypair.generate()
+      sandbox.mintSui(publisher.toSuiAddress(), Number(20n * MIST_PER_SUI))
+
+      checkTxSuccedded(
+        await publishPackageSigned(sandbox, './move-fixtures/shared', publisher, {
+          autoFund: false,
+          gasBudget: Number(MIST_PER_SUI),
+        }),
+      )
+    })
+  })
+
+  describe('Publish diagnostics', () => {
+    it('reports no diagnostics for a valid package', () => {
+      const { sandbox } = createSandboxClient()
+
+      expect(verifyPackage(sandbox, './move-fixtures/shared')).toEqual([])
+    })
+
+    it('reports missing dependencies as a linkage failure', () => {
+      const { sandbox } = createSandboxClient()
+
+      const [diagnostic] = verifyPackage(sandbox, './move-fixtures/shared', [])
+      expect(diagnostic).toMatchObject({ module: 'shared', check: 'linkage' })
+      expect(diagnostic.statusCode).toEqual(expect.any(Number))
+    })
+
+    it('throws structured error for undecodable modules', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      let error: unknown
+      try {
+        sandbox.publishPackage([[0, 1, 2]], [], sender.toSuiAddress())
+      } catch (e) {
+        error = e
+      }
+
+      expect(error).toBeInstanceOf(PublishError)
+      expect((error as PublishError).diagnostics).toMatchObject([{ module: null, check: 'deserialization' }])
+    })
+
+    it('throws structured error for upgrades failing verification', () => {
+      const { sandbox, packageId, upgradeCapId } = publishUpgradeablePackage()
+
+      expect(() => sandbox.upgradePackage(packageId, upgradeCapId, [[0, 1, 2]], [])).toThrow(PublishError)
+    })
+  })
+
+  describe('In-process compilation', () => {
+    it('publishes package straight from its directory', async () => {
+      const { client, sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const { packageId, namedAddresses, response } = sandbox.publishFromPath(
+        './move-fixtures/shared',
+        sender.toSuiAddress(),
+      )
+
+      checkTxSuccedded(response)
+      expect(namedAddresses.shared).toBe(packageId)
+      expect(namedAddresses.sui).toBe('0x0000000000000000000000000000000000000000000000000000000000000002')
+
+      const sharedClient = new SharedClient(client, packageId, sender)
+      expect(await sharedClient.readValue(await sharedClient.new())).toBe(0)
+    })
+
+    it('links local dependencies to the packages published in the sandbox', async () => {
+      const { client, sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const shared = sandbox.publishFromPath('./move-fixtures/shared', sender.toSuiAddress())
+      const dependent = sandbox.publishFromPath('./move-fixtures/dependent', sender.toSuiAddress())
+
+      checkTxSuccedded(dependent.response)
+      expect(dependent.namedAddresses.shared).toBe(shared.packageId)
+      expect(dependent.namedAddresses.dependent).toBe(dependent.packageId)
+
+      const sharedClient = new SharedClient(client, shared.packageId, sender)
+      const object = await sharedClient.new()
+
+      const tx = new Transaction()
+      tx.moveCall({
+        target: `${dependent.packageId}::dependent::double`,
+        arguments: [tx.object(object), tx.pure.u8(21)],
+      })
+      checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: sender }))
+
+      expect(await sharedClient.readValue(object)).toBe(42)
+    })
+
+    it('links dependencies of local dependencies', async () => {
+      const { client, sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const shared = sandbox.publishFromPath('./move-fixtures/shared', sender.toSuiAddress())
+      const dependent = sandbox.publishFromPath('./move-fixtures/dependent', sender.toSuiAddress())
+      const transitive = sandbox.publishFromPath('./move-fixtures/transitive', sender.toSuiAddress())
+
+      checkTxSuccedded(transitive.response)
+      expect(transitive.namedAddresses.shared).toBe(shared.packageId)
+      expect(transitive.namedAddresses.dependent).toBe(dependent.packageId)
+      expect(transitive.namedAddresses.transitive).toBe(transitive.packageId)
+
+      const sharedClient = new SharedClient(client, shared.packageId, sender)
+      const object = await sharedClient.new()
+
+      const tx = new Transaction()
+      tx.moveCall({
+        target: `${transitive.packageId}::transitive::quadruple`,
+        arguments: [tx.object(object), tx.pure.u8(10)],
+      })
+      checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: sender }))
+
+      expect(await sharedClient.readValue(object)).toBe(40)
+    })
+
+    it('rejects local dependencies not published in the sandbox', () => {
+      const sandbox = new SandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      expect(() => sandbox.publishFromPath('./move-fixtures/dependent', sender.toSuiAddress())).toThrow(
+        'Dependency shared is not published in the sandbox',
+      )
+    })
+  })
+
+  describe('Package upgrades', () => {
+    it('upgrades package and tracks versions against original id', () => {
+      const { sandbox, packageId, upgradeCapId } = publishUpgradeablePackage()
+
+      const upgradeResult = upgradePackage(sandbox, './move-fixtures/shared', packageId, upgradeCapId)
+      checkTxSuccedded(upgradeResult)
+
+      const upgradedId = upgradeResult.objectChanges!.find((change) => change.type === 'published')!.packageId
+      expect(upgradedId).not.toBe(packageId)
+
+      expect(sandbox.getPackageVersions(upgradedId)).toEqual({ '1': packageId, '2': upgradedId })
+    })
+
+    it('rejects upgrade of a superseded package version', () => {
+      const { sandbox, packageId, upgradeCapId } = publishUpgradeablePackage()
+
+      const firstUpgrade = checkTxSuccedded(
+        upgradePackage(sandbox, './move-fixtures/shared', packageId, upgradeCapId, 'dep_only'),
+      )
+      const upgradedId = firstUpgrade.objectChanges!.find((change) => change.type === 'published')!.packageId
+
+      checkTxFailed(upgradePackage(sandbox, './move-fixtures/shared', packageId, upgradeCapId))
+      checkTxSuccedded(upgradePackage(sandbox, './move-fixtures/shared', upgradedId, upgradeCapId))
+
+      expect(Object.keys(sandbox.getPackageVersions(packageId))).toEqual(['1', '2', '3'])
+    })
+
+    it('passes publish options through to the upgrade', () => {
+      const { sandbox, packageId, sender, upgradeCapId } = publishUpgradeablePackage()
+      const balance = sandbox.getBalance(sender.toSuiAddress())
+
+      checkTxSuccedded(
+        upgradePackage(sandbox, './move-fixtures/shared', packageId, upgradeCapId, 'compatible', {
+          autoFund: false,
+          name: 'shared_pkg',
+        }),
+      )
+
+      expect(sandbox.getBalance(sender.toSuiAddress())).toBeLessThan(balance)
+      expect(sandbox.resolvePackage('shared_pkg').originalId).toBe(packageId)
+    })
+
+    it('rejects upgrades the policy does not allow', () => {
+      const upgrade = (fixture: string, policy: UpgradePolicy) => {
+        const { sandbox, packageId, upgradeCapId } = publishUpgradeablePackage()
+
+        return upgradePackage(sandbox, fixture, packageId, upgradeCapId, policy)
+      }
+
+      checkTxFailed(upgrade('./move-fixtures/shared_incompatible', 'compatible'))
+      checkTxFailed(upgrade('./move-fixtures/shared_changed_body', 'additive'))
+      checkTxFailed(upgrade('./move-fixtures/shared_changed_body', 'dep_only'))
+
+      checkTxSuccedded(upgrade('./move-fixtures/shared_changed_body', 'compatible'))
+    })
+  })
+
+  describe('Package registry', () => {
+    it('resolves packages by name across upgrades', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const result = checkTxSuccedded(
+        publishPackage(sandbox, './move-fixtures/shared', sender.toSuiAddress(), { name: 'my_pkg' }),
+      )
+      const packageId = result.objectChanges!.find((change) => change.type === 'published')!.packageId
+      const upgradeCapId = findUpgradeCapId(result)
+
+      const upgradeResult = checkTxSuccedded(upgradePackage(sandbox, './move-fixtures/shared', packageId, upgradeCapId))
+      const upgradedId = upgradeResult.objectChanges!.find((change) => change.type === 'published')!.packageId
+
+      expect(sandbox.resolvePackage('my_pkg')).toEqual({
+        name: 'my_pkg',
+        originalId: packageId,
+        latestId: upgradedId,
+        versions: { '1': packageId, '2': upgradedId },
+      })
+      expect(sandbox.expandType('0x2::coin::Coin<my_pkg::shared::Test>')).toBe(
+        `0x2::coin::Coin<${packageId}::shared::Test>`,
+      )
+      expect(() => sandbox.resolvePackage('unknown')).toThrow('No package registered')
+    })
+
+    it('registers the Move package name when publishing from path', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const { packageId } = sandbox.publishFromPath('./move-fixtures/shared', sender.toSuiAddress())
+
+      expect(sandbox.resolvePackage('shared').originalId).toBe(packageId)
+
+      sandbox.registerPackage('alias', packageId)
+      expect(sandbox.expandType('alias::shared::Test')).toBe(`${packageId}::shared::Test`)
+    })
+
+    it('does not override named addresses assigned by the manifest', () => {
+      const { sandbox } = createSandboxClient()
+      const sender = Secp256k1Keypair.generate()
+
+      const first = sandbox.publishFromPath('./move-fixtures/shared', sender.toSuiAddress())
+      const second = sandbox.publishFromPath('./move-fixtures/shared', sender.toSuiAddress())
+
+      checkTxSuccedded(second.response)
+      expect(second.packageId).not.toBe(first.packageId)
+      expect(second.namedAddresses.shared).toBe(second.packageId)
+    })
+  })
+
+  describe('Normalized package RPCs', () => {
+    it('returns normalized modules, structs and arg types', async () => {
+      const { client, packageId } = publishSharedPackage()
+
+      const modules = await client.getNormalizedMoveModulesByPackage({ package: packageId })
+      expect(Object.keys(modules)).toEqual(['shared'])
+
+      const module = await client.getNormalizedMoveModule({ package: packageId, module: 'shared' })
+      expect(Object.keys(module.exposedFunctions)).toEqual(expect.arrayContaining(['new', 'set_value']))
+
+      const struct = await client.getNormalizedMoveStruct({ package: packageId, module: 'shared', struct: 'Test' })
+      expect(struct.fields.map((field) => field.name)).toEqual(['id', 'value'])
+
+      const argTypes = await client.getMoveFunctionArgTypes({
+        package: packageId,
+        module: 'shared',
+        function: 'set_value',
+      })
+      expect(argTypes).toEqual([{ Object: 'ByMutableReference' }, 'Pure'])
+    })
+  })
+
   describe('Clock package', () => {
     it('publishes package successfully', () => {
       const { publishResult } = publishClockPackage()
@@ -328,6 +1305,186 @@ describe('SuiSandboxClient', () => {
     })
   })
 
+  describe('Move aborts', () => {
+    const publishPool = async () => {
+      const { client, sandbox, packageId, sender } = publishTestPackage('./move-fixtures/pool')
+
+      const tx = new Transaction()
+      tx.moveCall({ target: `${packageId}::pool::new` })
+      const created = checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: sender }))
+      const pool = created.objectChanges!.find(
+        (change) => change.type === 'created' && change.objectType.endsWith('::pool::Pool'),
+      )
+
+      const withdraw = (fun: string) => {
+        const tx = new Transaction()
+        tx.moveCall({
+          target: `${packageId}::pool::${fun}`,
+          arguments: [tx.object(pool?.type === 'created' ? pool.objectId : ''), tx.pure.u64(1)],
+        })
+        tx.setSender(sender.toSuiAddress())
+        tx.setGasBudget(Number(MIST_PER_SUI))
+
+        return tx
+      }
+
+      return { client, sandbox, packageId, sender, withdraw }
+    }
+
+    it('decodes abort location and code', async () => {
+      const { client, sandbox, packageId, sender, withdraw } = await publishPool()
+
+      const { bytes, signature } = await withdraw('withdraw_unchecked').sign({ client, signer: sender })
+      const result = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      expect(result.abort).toMatchObject({
+        package: packageId,
+        module: 'pool',
+        function: 'withdraw_unchecked',
+        code: '3',
+        codeBigInt: 3n,
+        message: null,
+      })
+      expect(sandbox.getTransaction(result.digest).abort).toEqual(result.abort)
+    })
+
+    it('decodes #[error] constant messages', async () => {
+      const { client, sandbox, sender, withdraw } = await publishPool()
+
+      const { bytes, signature } = await withdraw('withdraw').sign({ client, signer: sender })
+      const result = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      expect(result.abort).toMatchObject({
+        module: 'pool',
+        function: 'withdraw',
+        errorName: 'EPoolEmpty',
+        message: 'Pool has no liquidity left',
+      })
+      expect(result.abort!.codeBigInt).toBe(BigInt(result.abort!.code))
+      expect(result.abort!.codeBigInt).toBeGreaterThan(BigInt(Number.MAX_SAFE_INTEGER))
+    })
+
+    it('records a per-instruction trace of a failing transaction', async () => {
+      const { client, sandbox, sender, withdraw } = await publishPool()
+
+      const { bytes, signature } = await withdraw('withdraw_nested').sign({ client, signer: sender })
+      const traceFile = join(mkdtempSync(join(tmpdir(), 'trace-')), 'withdraw.json.zst')
+      const { response, trace } = sandbox.executeTransactionBlockWithTrace({
+        transactionBlock: bytes,
+        signature,
+        traceFile,
+      })
+
+      checkTxFailed(response)
+      // zstd frame magic number
+      expect([...trace!.subarray(0, 4)]).toEqual([0x28, 0xb5, 0x2f, 0xfd])
+      expect(readFileSync(traceFile)).toEqual(trace)
+    })
+
+    it('collects coverage of executed Move code', async () => {
+      const { client, sandbox, sender, withdraw } = await publishPool()
+      sandbox.enableCoverage()
+
+      const { bytes, signature } = await withdraw('withdraw_nested').sign({ client, signer: sender })
+      checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      const lcov = sandbox.exportCoverage('lcov')
+      expect(lcov).toContain('pool.move')
+      expect(lcov).toContain('FNDA:1,check_liquidity')
+      expect(lcov).toContain('FNDA:0,new')
+      expect(sandbox.exportCoverage('move').length).toBeGreaterThan(0)
+
+      sandbox.resetCoverage()
+      expect(sandbox.exportCoverage('lcov')).toBe('')
+    })
+
+    it('counts function calls in coverage by package version', async () => {
+      const { client, sandbox, packageId, sender, publishResult } = publishTestPackage('./move-fixtures/pool')
+      const upgraded = checkTxSuccedded(
+        upgradePackage(sandbox, './move-fixtures/pool', packageId, findUpgradeCapId(publishResult)),
+      )
+      const upgradedId = upgraded.objectChanges!.find((change) => change.type === 'published')!.packageId
+      sandbox.enableCoverage()
+
+      // the loop jumps back to the first instruction of `countdown` on every iteration
+      const tx = new Transaction()
+      tx.moveCall({ target: `${packageId}::pool::countdown`, arguments: [tx.pure.u64(3)] })
+      tx.moveCall({ target: `${upgradedId}::pool::countdown`, arguments: [tx.pure.u64(3)] })
+      checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: sender }))
+
+      const lcov = sandbox.exportCoverage('lcov')
+      expect(lcov.match(/^SF:.*pool\.move$/gm)).toHaveLength(2)
+      expect(lcov.match(/^FNDA:1,countdown$/gm)).toHaveLength(2)
+    })
+
+    it('profiles gas per function, command and object', async () => {
+      const { client, sandbox, packageId, sender, withdraw } = await publishPool()
+      sandbox.enableGasProfiler()
+
+      const tx = new Transaction()
+      tx.moveCall({ target: `${packageId}::pool::new` })
+      const created = checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: tx, signer: sender }))
+
+      const profile = sandbox.getGasProfile(created.digest)!
+      expect(profile.gasUsed).toEqual(created.effects!.gasUsed)
+      expect(profile.commands).toEqual([
+        expect.objectContaining({ kind: 'MoveCall', function: expect.stringMatching(/::pool::new$/) }),
+      ])
+      const pool = profile.storage.find((object) => object.objectType?.endsWith('::pool::Pool'))
+      expect(pool!.bytesWritten).toBeGreaterThan(0)
+      expect(pool!.storageRebate).toBe(0)
+
+      const { bytes, signature } = await withdraw('withdraw_nested').sign({ client, signer: sender })
+      const failed = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      const { functions, commands, foldedStacks } = sandbox.getGasProfile(failed.digest)!
+      const nested = functions.find((fun) => fun.function.endsWith('::pool::withdraw_nested'))!
+      const check = functions.find((fun) => fun.function.endsWith('::pool::check_liquidity'))!
+      expect(nested.inclusive).toBeGreaterThanOrEqual(check.inclusive)
+      expect(commands[0].computationUnits).toBe(nested.inclusive)
+      expect(foldedStacks).toMatch(/^command#0;.*::pool::withdraw_nested;.*::pool::check_liquidity \d+$/m)
+
+      sandbox.disableGasProfiler()
+      const next = new Transaction()
+      next.moveCall({ target: `${packageId}::pool::new` })
+      const { digest } = checkTxSuccedded(await client.signAndExecuteTransaction({ transaction: next, signer: sender }))
+      expect(sandbox.getGasProfile(digest)).toBeNull()
+    })
+
+    it('attributes init functions to the Publish command that created their package', () => {
+      const { sandbox, sender } = publishTestPackage('./move-fixtures/pool')
+      sandbox.enableGasProfiler()
+
+      const published = publishPackage(sandbox, './move-fixtures/admin', sender.toSuiAddress())
+
+      const { commands, foldedStacks } = sandbox.getGasProfile(published.digest)!
+      expect(commands[0]).toMatchObject({ kind: 'Publish', computationUnits: expect.any(Number) })
+      expect(foldedStacks).toMatch(/^command#0;.*::admin::init \d+$/m)
+    })
+
+    it('captures the Move call stack with source lines', async () => {
+      const { client, sandbox, sender, withdraw } = await publishPool()
+      sandbox.enableAbortStackTraces()
+
+      const { bytes, signature } = await withdraw('withdraw_nested').sign({ client, signer: sender })
+      const { abort } = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      expect(abort!.stack.map((frame) => frame.function)).toEqual(['check_liquidity', 'withdraw_nested'])
+      expect(abort!.stack[0]).toMatchObject({ module: 'pool', file: expect.stringContaining('pool.move') })
+      expect(abort!.stack[0].line).toBeGreaterThan(abort!.stack[1].line!)
+      expect(formatMoveStackTrace(abort!)).toContain('at pool::check_liquidity (')
+    })
+
+    it('does not capture the Move call stack unless enabled', async () => {
+      const { client, sandbox, sender, withdraw } = await publishPool()
+
+      const { bytes, signature } = await withdraw('withdraw_nested').sign({ client, signer: sender })
+      const { abort } = checkTxFailed(sandbox.executeTransactionBlock({ transactionBlock: bytes, signature }))
+
+      expect(abort!.stack).toEqual([])
+    })
+  })
+
   describe('Dynamic package', () => {
     it('publishes package successfully', () => {
       const { publishResult } = publishDynamicPackage()
@@ -359,7 +1516,43 @@ describe('SuiSandboxClient', () => {
     })
   })
 
+  describe('Display package', () => {
+    it('renders display fields when requested', async () => {
+      const { client, sandbox, packageId, sender } = publishDisplayPackage()
+
+      const hero = sandbox.createObject({
+        type: `${packageId}::hero::Hero`,
+        fields: { name: 'Arthur', level: '3' },
+        owner: { AddressOwner: sender.toSuiAddress() },
+      })
+
+      const withDisplay = await client.getObject({ id: hero, options: { showDisplay: true } })
+      expect(withDisplay.data!.display!.data).toEqual({ name: 'Arthur', description: 'Level 3 hero' })
+
+      const withoutDisplay = await client.getObject({ id: hero })
+      expect(withoutDisplay.data!.display).toBeUndefined()
+    })
+  })
+
   describe('Object queries', () => {
+    it('decodes object with table entries inlined', async () => {
+      const { client, packageId, sender, sandbox } = publishDynamicPackage()
+
+      const dynamicClient = new DynamicClient(client, packageId, sender)
+      const dynamic = await dynamicClient.new()
+
+      const decoded = sandbox.getDecoded(dynamic, { depth: 1 })
+      const table = decoded.content.fields.values
+
+      expect(decoded.content.type).toBe(`${packageId}::dynamic_fields::Dynamic`)
+      expect(table.fields.size).toBe('4')
+      expect(table.dynamicFields.map((entry: any) => entry.name)).toEqual([0, 1, 2, 3])
+      expect(table.dynamicFields[2].value.fields.value).toBe(2)
+
+      const shallow = sandbox.getDecoded(dynamic, { depth: 0 })
+      expect(shallow.content.fields.values.dynamicFields).toBeUndefined()
+    })
+
     it('tryGetPastObject', async () => {
       const { client, packageId, sender, sandbox } = publishClockPackage()
 
@@ -392,6 +1585,109 @@ describe('SuiSandboxClient', () => {
       expect(notExistingObject.status).toBe('ObjectNotExists')
     })
 
+    it('creates typed object from fields', async () => {
+      const { client, packageId, sender, sandbox } = publishSharedPackage()
+
+      const shared = sandbox.createObject({
+        type: `${packageId}::shared::Test`,
+        fields: { value: 42 },
+        owner: { Shared: { initial_shared_version: 1 } },
+      })
+
+      const sharedClient = new SharedClient(client, packageId, sender)
+      expect(await sharedClient.readValue(shared)).toBe(42)
+
+      checkTxSuccedded(await sharedClient.setValue(shared, 7))
+      expect(await sharedClient.readValue(shared)).toBe(7)
+    })
+
+    it('patches object fields and bumps version', async () => {
+      const { client, packageId, sender, sandbox } = publishSharedPackage()
+
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+      const { data } = await client.getObject({ id: shared })
+
+      const version = sandbox.patchFields(shared, { value: 99 })
+
+      expect(version).toBe(Number(data!.version) + 1)
+      expect(await sharedClient.readValue(shared)).toBe(99)
+      expect((await client.tryGetPastObject({ id: shared, version: Number(data!.version) })).status).toBe(
+        'VersionFound',
+      )
+    })
+
+    it('rewrites object ownership', async () => {
+      const { client, sandbox, sender, recipient, coinIds } = setupTransferTest()
+
+      sandbox.transferObject(coinIds[1], recipient.toSuiAddress())
+      expect(sandbox.getBalance(recipient.toSuiAddress())).toBe(Number(INITIAL_BALANCE))
+      expect(sandbox.getBalance(sender.toSuiAddress())).toBe(Number(INITIAL_BALANCE) * 2)
+
+      sandbox.freezeObject(coinIds[2])
+      const frozen = await client.getObject({ id: coinIds[2] })
+      expect(frozen.data!.owner).toBe('Immutable')
+      expect(sandbox.getBalance(sender.toSuiAddress())).toBe(Number(INITIAL_BALANCE))
+
+      const original = await client.tryGetPastObject({ id: coinIds[1], version: 1 })
+      expect(original.status).toBe('VersionFound')
+
+      expect(() => sandbox.transferObject(coinIds[2], sender.toSuiAddress())).toThrow('immutable')
+      expect(() => sandbox.shareObject(coinIds[2])).toThrow('immutable')
+    })
+
+    it('keeps the initial shared version when sharing a shared object again', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const shared = await sharedClient.new()
+      const { data } = await client.getObject({ id: shared, options: { showOwner: true } })
+
+      expect(sandbox.shareObject(shared)).toBe(Number(data!.version))
+      const reshared = await client.getObject({ id: shared, options: { showOwner: true } })
+      expect(reshared.data!.owner).toEqual(data!.owner)
+
+      await sharedClient.setValue(shared, 7)
+      expect(await sharedClient.readValue(shared)).toBe(7)
+    })
+
+    it('places object under a parent as dynamic object field', async () => {
+      const { client, sandbox, packageId, sender } = publishSharedPackage()
+
+      const sharedClient = new SharedClient(client, packageId, sender)
+      const parent = await sharedClient.new()
+      const coin = sandbox.coinApi().mintSui(sender.toSuiAddress(), 1000)
+
+      const fieldId = sandbox.addDynamicObjectField(parent, coin)
+
+      const fields = await client.getDynamicFields({ parentId: parent })
+      expect(fields.data.length).toBe(1)
+      expect(fields.data[0].objectId).toBe(`0x${coin}`)
+
+      const field = await client.getObject({ id: fieldId })
+      expect(field.data!.owner).toEqual({ ObjectOwner: parent })
+
+      const other = await sharedClient.new()
+      expect(() => sandbox.addDynamicObjectField(other, coin)).toThrow('not address owned')
+      expect(() => sandbox.transferObject(coin, sender.toSuiAddress())).toThrow('is a child of')
+      expect(() => sandbox.shareObject(coin)).toThrow('is a child of')
+      expect(() => sandbox.freezeObject(coin)).toThrow('is a child of')
+      expect((await client.getDynamicFields({ parentId: parent })).data.length).toBe(1)
+    })
+
+    it('does not create objects under a parent', async () => {
+      const { client, packageId, sender, sandbox } = publishSharedPackage()
+      const parent = await new SharedClient(client, packageId, sender).new()
+
+      expect(() =>
+        sandbox.createObject({
+          type: `${packageId}::shared::Test`,
+          fields: { value: 42 },
+          owner: { ObjectOwner: parent },
+        }),
+      ).toThrow('dynamic object field')
+    })
+
     it('transaction block queries', async () => {
       const { client, packageId, sender, sandbox } = publishClockPackage()
 
@@ -446,24 +1742,29 @@ function setupTransferTest() {
   return { client, sandbox, sender, recipient, coinIds }
 }
 
-function createTransferTransaction(sender: Secp256k1Keypair, recipient: Secp256k1Keypair, coinIds: string[]) {
+function createTransferTransaction(
+  sandbox: SandboxClient,
+  sender: Secp256k1Keypair,
+  recipient: Secp256k1Keypair,
+  coinIds: string[],
+) {
   const tx = new Transaction()
 
   tx.transferObjects([coinIds[1], coinIds[2]], recipient.toSuiAddress())
   tx.setSender(sender.toSuiAddress())
   tx.setGasBudget(Number(GAS_BUDGET))
   tx.setGasPrice(GAS_PRICE)
-  tx.setGasPayment([
-    {
-      objectId: coinIds[0],
-      version: '1',
-      digest: '11111111111111111111111111111111',
-    },
-  ])
+  tx.setGasPayment([objectRef(sandbox, coinIds[0])])
 
   return tx
 }
 
+function objectRef(sandbox: SandboxClient, objectId: string) {
+  const { data } = sandbox.getObject({ id: objectId })
+
+  return { objectId, version: data.version, digest: data.digest }
+}
+
 function publishTestPackage(packageDir: string) {
   const { client, sandbox } = createSandboxClient()
   const sender = Secp256k1Keypair.generate()
@@ -476,13 +1777,30 @@ function publishTestPackage(packageDir: string) {
   return { client, sandbox, packageId, sender, publishResult }
 }
 
-function checkTxSuccedded(res: SuiTransactionBlockResponse) {
+function gasUsed(total: number) {
+  return {
+    computationCost: '0',
+    storageCost: '0',
+    storageRebate: String(-total),
+    nonRefundableStorageFee: '0',
+  }
+}
+
+function findUpgradeCapId(publishResult: SuiTransactionBlockResponse) {
+  const upgradeCap = publishResult.objectChanges!.find(
+    (change) => change.type === 'created' && change.objectType === '0x2::package::UpgradeCap',
+  )
+
+  return upgradeCap?.type === 'created' ? upgradeCap.objectId : ''
+}
+
+function checkTxSuccedded<T extends SuiTransactionBlockResponse>(res: T): T {
   expect(res.errors).toBeUndefined()
 
   return res
 }
 
-function checkTxFailed(res: SuiTransactionBlockResponse) {
+function checkTxFailed<T extends SuiTransactionBlockResponse>(res: T): T {
   expect(res.errors).toBeDefined()
 
   return res